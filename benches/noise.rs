@@ -0,0 +1,85 @@
+//! Times the perlin noise sketch: 250,000 round points, one per pixel, each
+//! in its own color. Drawn once with a `point` call per pixel, and once with
+//! a single `colored_points` call.
+//!
+//! Each is timed twice: tessellated only, and rendered by the software
+//! rasterizer. The points are made before either starts.
+//!
+//! Run with `cargo bench --bench noise`.
+
+use std::{
+    hint::black_box,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use noise::{NoiseFn, Perlin};
+use processing::{
+    testing::{render_sketch, tessellate_sketch},
+    Color, GraphicsP2D, Processing, StrokeCap,
+};
+
+const SIZE: u32 = 500;
+const RUNS: usize = 7;
+
+fn main() {
+    bench("noise", |p, points, colors| {
+        for (&(x, y), &color) in points.iter().zip(colors) {
+            p.stroke(color);
+            p.point(x, y);
+        }
+    });
+    bench("noise (bulk)", |p, points, colors| {
+        p.colored_points(points, colors);
+    });
+}
+
+/// Draws the points, each in the color of the same index
+type Draw = fn(&mut Processing<(), GraphicsP2D>, &[(f32, f32)], &[Color]);
+
+fn bench(name: &str, draw: Draw) {
+    let noise = Perlin::new(0);
+    let mut points = Vec::new();
+    let mut colors = Vec::new();
+    for x in 0..SIZE {
+        for y in 0..SIZE {
+            let n = noise.get([x as f64 / 20.0, y as f64 / 20.0]) * 122.0 + 122.0;
+            points.push((x as f32, y as f32));
+            colors.push(Color::red(n as u8));
+        }
+    }
+
+    let input = Rc::new((points, colors));
+    let sketch = || {
+        let input = Rc::clone(&input);
+        move |p: &mut Processing<(), GraphicsP2D>| {
+            p.stroke_weight(2.0);
+            p.stroke_cap(StrokeCap::Round);
+            draw(p, &input.0, &input.1);
+        }
+    };
+    report(name, "tessellate", || {
+        black_box(tessellate_sketch(SIZE, SIZE, sketch()));
+    });
+    report(name, "render", || {
+        black_box(render_sketch(SIZE, SIZE, sketch()).unwrap());
+    });
+}
+
+/// Times `run` without the preparation done before it starts
+fn report(name: &str, stage: &str, run: impl Fn()) {
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+
+    println!(
+        "{name} {SIZE}x{SIZE}, {stage}: median {:?}, best {:?} over {RUNS} runs",
+        times[RUNS / 2],
+        times[0]
+    );
+}
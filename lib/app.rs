@@ -1,15 +1,31 @@
 use glium::backend::glutin::SimpleWindowBuilder;
-use winit::{event_loop::EventLoop};
+use image::RgbaImage;
+use winit::event_loop::EventLoop;
 
 use crate::{
     painter::Painter,
     processing::{DrawFn, KeyPressedFn, MouseClickedFn, MouseMovedFn, Processing, SetupFn},
+    rasterizer::Rasterizer,
     settings::WindowSettings,
     traits::Renderer,
 };
 
 pub trait Application {
     fn run(self) -> anyhow::Result<()>;
+
+    /// Runs `setup` and then `frames` iterations of `draw` with the software
+    /// [`Rasterizer`] instead of opening a window, returning every rendered frame.
+    fn run_headless(self, frames: usize) -> anyhow::Result<Vec<RgbaImage>>;
+
+    /// Runs `setup` and a single `draw` headlessly and returns the rendered frame.
+    fn render(self) -> anyhow::Result<RgbaImage>
+    where
+        Self: Sized,
+    {
+        self.run_headless(1)?
+            .pop()
+            .ok_or(anyhow::anyhow!("No frame was rendered"))
+    }
 }
 
 pub struct App<S, R: Renderer + Default> {
@@ -90,11 +106,29 @@ impl<S, R: Renderer + Default> Application for App<S, R> {
             include_str!("shaders/fragment.glsl"),
             None,
         )?;
+        let instanced_program = glium::Program::from_source(
+            &display,
+            include_str!("shaders/instanced_vertex.glsl"),
+            include_str!("shaders/fragment.glsl"),
+            None,
+        )?;
+        let sdf_program = glium::Program::from_source(
+            &display,
+            include_str!("shaders/sdf_vertex.glsl"),
+            include_str!("shaders/sdf_fragment.glsl"),
+            None,
+        )?;
 
         let processing = Processing::new(
             self.state,
             self.window_settings,
-            Painter::new(window, display, program),
+            Box::new(Painter::new(
+                window,
+                display,
+                program,
+                instanced_program,
+                sdf_program,
+            )),
             self.draw,
             self.mouse_clicked,
             self.mouse_moved,
@@ -103,4 +137,34 @@ impl<S, R: Renderer + Default> Application for App<S, R> {
 
         processing.run(event_loop, self.setup.unwrap_or(Box::new(|_| {})))
     }
+
+    fn run_headless(self, frames: usize) -> anyhow::Result<Vec<RgbaImage>> {
+        let (processing, setup) = self.headless();
+        processing.run_headless(setup, frames)
+    }
+}
+
+impl<S, R: Renderer + Default> App<S, R> {
+    /// Runs `setup` and tessellates what it drew, without rasterizing it.
+    /// Returns how many vertices and instances the frame holds.
+    pub(crate) fn tessellate(self) -> usize {
+        let (processing, setup) = self.headless();
+        processing.tessellate_headless(setup)
+    }
+
+    /// The sketch drawn by the software [`Rasterizer`], and its `setup`
+    fn headless(self) -> (Processing<S, R>, SetupFn<S, R>) {
+        let rasterizer = Rasterizer::new(self.window_settings.width, self.window_settings.height);
+
+        let processing = Processing::new(
+            self.state,
+            self.window_settings,
+            Box::new(rasterizer),
+            self.draw,
+            self.mouse_clicked,
+            self.mouse_moved,
+            self.key_pressed,
+        );
+        (processing, self.setup.unwrap_or(Box::new(|_| {})))
+    }
 }
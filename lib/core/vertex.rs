@@ -1,4 +1,7 @@
-use crate::{Color};
+use crate::{
+    tess::{fill::FillVertex, sdf},
+    Color, Matrix2D,
+};
 
 #[derive(Debug, Copy, Clone)]
 pub struct Vertex {
@@ -13,6 +16,29 @@ impl Vertex {
             color: color.into(),
         }
     }
+
+    pub(crate) fn position(&self) -> [f32; 3] {
+        self.position
+    }
+
+    pub(crate) fn color(&self) -> [f32; 4] {
+        self.color
+    }
+}
+
+impl FillVertex for Vertex {
+    fn xy(&self) -> (f32, f32) {
+        (self.position[0], self.position[1])
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vertex {
+            position: std::array::from_fn(|i| {
+                self.position[i] + (other.position[i] - self.position[i]) * t
+            }),
+            color: std::array::from_fn(|i| self.color[i] + (other.color[i] - self.color[i]) * t),
+        }
+    }
 }
 
 pub fn vert2d(x: f32, y: f32, color: Color) -> Vertex {
@@ -24,3 +50,113 @@ pub fn vert3d(x: f32, y: f32, z: f32, color: Color) -> Vertex {
 }
 
 implement_vertex!(Vertex, position, color);
+
+/// Where and in which color to draw one copy of an instanced mesh
+#[derive(Debug, Copy, Clone)]
+pub struct Instance {
+    transform_x: [f32; 3], // m00, m01, m02
+    transform_y: [f32; 3], // m10, m11, m12
+    instance_color: [f32; 4],
+}
+
+impl Instance {
+    pub fn new(matrix: Matrix2D, color: Color) -> Instance {
+        Instance {
+            transform_x: [matrix.m00, matrix.m01, matrix.m02],
+            transform_y: [matrix.m10, matrix.m11, matrix.m12],
+            instance_color: color.into(),
+        }
+    }
+
+    /// `vertex` of the mesh as drawn for this instance, like the instanced
+    /// vertex shader does
+    pub(crate) fn apply(&self, vertex: &Vertex) -> Vertex {
+        let [x, y, z] = vertex.position;
+        let [m00, m01, m02] = self.transform_x;
+        let [m10, m11, m12] = self.transform_y;
+        Vertex {
+            position: [m00 * x + m01 * y + m02, m10 * x + m11 * y + m12, z],
+            color: std::array::from_fn(|i| vertex.color[i] * self.instance_color[i]),
+        }
+    }
+}
+
+implement_vertex!(Instance, transform_x, transform_y, instance_color);
+
+/// Corner of the quad of a shape drawn from its signed distance. Apart from
+/// the positions, all the corners of a quad hold the same values.
+#[derive(Debug, Copy, Clone)]
+pub struct SdfVertex {
+    position: [f32; 3],
+    color: [f32; 4],
+    /// Position in the space of the shape, centered on it
+    local: [f32; 2],
+    /// Semi-axes of an ellipse, or half sides of a rounded box
+    size: [f32; 2],
+    /// Top right, bottom right, top left and bottom left radii of a rounded box
+    radii: [f32; 4],
+    /// Half the weight of the stroked outline, or 0 when the shape is filled
+    half_weight: f32,
+    /// 1 for an ellipse, 0 for a rounded box
+    ellipse: f32,
+}
+
+impl SdfVertex {
+    pub(crate) fn new(
+        (x, y): (f32, f32),
+        color: Color,
+        local: (f32, f32),
+        size: (f32, f32),
+        radii: [f32; 4],
+        half_weight: f32,
+        ellipse: bool,
+    ) -> SdfVertex {
+        SdfVertex {
+            position: [x, y, 0.0],
+            color: color.into(),
+            local: [local.0, local.1],
+            size: [size.0, size.1],
+            radii,
+            half_weight,
+            ellipse: if ellipse { 1.0 } else { 0.0 },
+        }
+    }
+
+    pub(crate) fn position(&self) -> [f32; 3] {
+        self.position
+    }
+
+    pub(crate) fn color(&self) -> [f32; 4] {
+        self.color
+    }
+
+    pub(crate) fn local(&self) -> [f32; 2] {
+        self.local
+    }
+
+    /// Signed distance from `local`, in the space of the shape, to the area
+    /// drawn, like the SDF fragment shader computes it
+    pub(crate) fn distance(&self, local: [f32; 2]) -> f32 {
+        let distance = if self.ellipse > 0.5 {
+            sdf::ellipse(local, self.size)
+        } else {
+            sdf::rounded_box(local, self.size, self.radii)
+        };
+        if self.half_weight > 0.0 {
+            distance.abs() - self.half_weight
+        } else {
+            distance
+        }
+    }
+}
+
+implement_vertex!(
+    SdfVertex,
+    position,
+    color,
+    local,
+    size,
+    radii,
+    half_weight,
+    ellipse
+);
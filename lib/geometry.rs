@@ -1,11 +1,17 @@
+use std::ops::Range;
+
 use glium::index;
 
 use crate::{
-    core::vertex::{vert3d},
-    gl_shape::LazyGlShape,
+    core::vertex::{vert2d, vert3d, Vertex},
+    gl_shape::GlFrame,
+    primitives::shapes_2d::{Bezier, Point, CURVE_TOLERANCE},
+    settings::{FillRule, StrokeStyle},
+    tess::{fill, fns::gl_triangle, stroke},
     Color,
 };
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GeometryKind {
     Points,
     Lines,
@@ -17,6 +23,13 @@ pub enum GeometryKind {
     Polygon,
 }
 
+/// Whether `end_shape` connects the last vertex back to the first one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CloseMode {
+    Open,
+    Close,
+}
+
 impl From<GeometryKind> for index::PrimitiveType {
     fn from(val: GeometryKind) -> Self {
         match val {
@@ -25,13 +38,25 @@ impl From<GeometryKind> for index::PrimitiveType {
             GeometryKind::Triangles => index::PrimitiveType::TrianglesList,
             GeometryKind::TriangleFan => index::PrimitiveType::TriangleFan,
             GeometryKind::TriangleStrip => index::PrimitiveType::TriangleStrip,
+            // quads are split into triangles by `Geometry::tessellate`
             GeometryKind::Quads => index::PrimitiveType::TrianglesList,
-            GeometryKind::QuadStrip => index::PrimitiveType::TriangleStrip,
+            GeometryKind::QuadStrip => index::PrimitiveType::TrianglesList,
             GeometryKind::Polygon => index::PrimitiveType::TrianglesList,
         }
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum GeometryError {
+    #[error("a {kind:?} shape cannot have {count} vertices, it needs {expected}")]
+    InvalidVertexCount {
+        kind: GeometryKind,
+        count: usize,
+        expected: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct GeometryVertex {
     x: f32,
     y: f32,
@@ -59,11 +84,29 @@ impl GeometryVertex {
             stroke_weight,
         }
     }
+
+    fn point(&self) -> Point {
+        Point::new((self.x, self.y))
+    }
+
+    /// The same style at another position
+    fn moved_to(self, point: Point) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+            ..self
+        }
+    }
 }
 
 pub struct Geometry {
     kind: GeometryKind,
     vertices: Vec<GeometryVertex>,
+    /// Index of the first vertex of each contour after the outer one
+    contours: Vec<usize>,
+    in_contour: bool,
+    /// Vertices given to `curve_vertex` since the last other kind of vertex
+    curve_vertices: Vec<GeometryVertex>,
 }
 
 impl Geometry {
@@ -71,23 +114,306 @@ impl Geometry {
         Self {
             kind,
             vertices: Vec::new(),
+            contours: Vec::new(),
+            in_contour: false,
+            curve_vertices: Vec::new(),
         }
     }
 
     pub fn push_vertex(&mut self, vertex: GeometryVertex) {
+        self.curve_vertices.clear();
         self.vertices.push(vertex);
     }
 
-    fn vertices(&mut self) -> Vec<GeometryVertex> {
-        self.vertices.drain(..).collect()
+    /// Adds a cubic Bezier curve from the last vertex to `end`.
+    pub fn bezier_vertex(&mut self, c1: Point, c2: Point, end: GeometryVertex) {
+        let start = self.last_vertex("bezier_vertex");
+        let bezier = Bezier::new(
+            (start.x, start.y),
+            (c1.x, c1.y),
+            (c2.x, c2.y),
+            (end.x, end.y),
+        );
+        self.push_curve(bezier, end);
+        self.curve_vertices.clear();
+    }
+
+    /// Adds a quadratic Bezier curve from the last vertex to `end`.
+    pub fn quadratic_vertex(&mut self, control: Point, end: GeometryVertex) {
+        let start = self.last_vertex("quadratic_vertex");
+        let bezier = Bezier::quadratic((start.x, start.y), (control.x, control.y), (end.x, end.y));
+        self.push_curve(bezier, end);
+        self.curve_vertices.clear();
+    }
+
+    /// Adds a point of a Catmull-Rom spline. From the fourth point on, each
+    /// one extends the curve to the point before it; the first and last
+    /// points only shape the ends of the curve.
+    pub fn curve_vertex(&mut self, vertex: GeometryVertex, tightness: f32) {
+        self.expect_polygon("curve_vertex");
+        self.curve_vertices.push(vertex);
+
+        let n = self.curve_vertices.len();
+        if n < 4 {
+            return;
+        }
+        let [a, b, c, d] = [0, 1, 2, 3].map(|i| self.curve_vertices[n - 4 + i]);
+        if n == 4 {
+            self.vertices.push(b);
+        }
+        let curve = Bezier::curve((a.x, a.y), (b.x, b.y), (c.x, c.y), (d.x, d.y), tightness);
+        self.push_curve(curve, c);
+    }
+
+    /// Flattens `curve` into vertices styled like `end`, after the curve's start
+    fn push_curve(&mut self, curve: Bezier, end: GeometryVertex) {
+        let mut points = Vec::new();
+        curve.flatten(CURVE_TOLERANCE, &mut points);
+        // the last point is `end` itself, kept exact
+        points.pop();
+        self.vertices
+            .extend(points.into_iter().map(|point| end.moved_to(point)));
+        self.vertices.push(end);
+    }
+
+    fn last_vertex(&self, function: &str) -> GeometryVertex {
+        self.expect_polygon(function);
+        match self.vertices.last() {
+            Some(&vertex) => vertex,
+            None => panic!("vertex() has to be called before {function}()"),
+        }
+    }
+
+    fn expect_polygon(&self, function: &str) {
+        if !matches!(self.kind, GeometryKind::Polygon) {
+            panic!("{function}() can only be used in a GeometryKind::Polygon shape");
+        }
+    }
+
+    pub fn begin_contour(&mut self) {
+        self.expect_polygon("begin_contour");
+        if self.in_contour {
+            panic!("begin_contour() has already been called");
+        }
+        self.in_contour = true;
+        self.contours.push(self.vertices.len());
+        self.curve_vertices.clear();
     }
 
-    pub fn tessellate(mut self) -> LazyGlShape {
-        let shape = self
-            .vertices()
-            .into_iter()
-            .map(|v| vert3d(v.x, v.y, v.z, v.fill.unwrap_or(Color::TRANSPARENT)));
+    pub fn end_contour(&mut self) {
+        if !self.in_contour {
+            panic!("begin_contour() has not been called");
+        }
+        self.in_contour = false;
+        self.curve_vertices.clear();
+    }
 
-        LazyGlShape::new(shape, self.kind.into())
+    /// Ranges of `vertices` making up the outer contour and each hole
+    fn contours(&self) -> Vec<Range<usize>> {
+        let bounds = std::iter::once(0)
+            .chain(self.contours.iter().copied())
+            .chain(std::iter::once(self.vertices.len()));
+        bounds
+            .clone()
+            .zip(bounds.skip(1))
+            .map(|(start, end)| start..end)
+            .collect()
     }
+
+    /// Checks that the shape is complete and can be tessellated.
+    pub fn check(&self) -> Result<(), GeometryError> {
+        if self.in_contour {
+            panic!("end_contour() has to be called before end_shape()");
+        }
+        self.validate()
+    }
+
+    /// Tessellates the fill and the stroke of a shape that passed `check`.
+    pub fn tessellate(
+        self,
+        rule: FillRule,
+        style: StrokeStyle,
+        close: CloseMode,
+        frame: &mut GlFrame,
+    ) {
+        self.tessellate_fill(rule, frame);
+        self.tessellate_stroke(style, close, frame);
+    }
+
+    /// Checks that the vertices split evenly into the primitives of the kind.
+    /// An empty shape is always valid and draws nothing.
+    // `usize::is_multiple_of` is too recent to require of every toolchain
+    #[allow(clippy::manual_is_multiple_of)]
+    fn validate(&self) -> Result<(), GeometryError> {
+        let count = self.vertices.len();
+        let expected = match self.kind {
+            GeometryKind::Lines if count % 2 != 0 => "an even number",
+            GeometryKind::Triangles if count % 3 != 0 => "a multiple of 3",
+            GeometryKind::TriangleFan | GeometryKind::TriangleStrip if count < 3 => "at least 3",
+            GeometryKind::Quads if count % 4 != 0 => "a multiple of 4",
+            GeometryKind::QuadStrip if count < 4 || count % 2 != 0 => {
+                "an even number of at least 4"
+            }
+            _ => return Ok(()),
+        };
+
+        if count == 0 {
+            return Ok(());
+        }
+        Err(GeometryError::InvalidVertexCount {
+            kind: self.kind,
+            count,
+            expected,
+        })
+    }
+
+    fn tessellate_fill(&self, rule: FillRule, frame: &mut GlFrame) {
+        let stroke_only = matches!(self.kind, GeometryKind::Points | GeometryKind::Lines);
+        if stroke_only || self.vertices.iter().all(|v| v.fill.is_none()) {
+            return;
+        }
+
+        let vertex =
+            |v: &GeometryVertex| vert3d(v.x, v.y, v.z, v.fill.unwrap_or(Color::TRANSPARENT));
+        let primitive = self.kind.into();
+        match self.kind {
+            // the triangulation is built on its own before it is copied in
+            GeometryKind::Polygon => {
+                let contours: Vec<Vec<Vertex>> = self
+                    .contours()
+                    .into_iter()
+                    .map(|range| self.vertices[range].iter().map(vertex).collect())
+                    .collect();
+                frame.push(fill::polygon(&contours, rule), primitive);
+            }
+            GeometryKind::Quads => frame.push(
+                self.vertices
+                    .chunks_exact(4)
+                    .flat_map(|q| [0, 1, 2, 0, 2, 3].map(|i| vertex(&q[i]))),
+                primitive,
+            ),
+            // each quad is made of two pairs of vertices, the second pair in
+            // the same order as the first, so the quad goes 0, 1, 3, 2
+            GeometryKind::QuadStrip => frame.push(
+                self.vertices
+                    .windows(4)
+                    .step_by(2)
+                    .flat_map(|q| [0, 1, 3, 0, 3, 2].map(|i| vertex(&q[i]))),
+                primitive,
+            ),
+            _ => frame.push(self.vertices.iter().map(vertex), primitive),
+        }
+    }
+
+    fn tessellate_stroke(&self, style: StrokeStyle, close: CloseMode, frame: &mut GlFrame) {
+        if let GeometryKind::Points = self.kind {
+            for v in &self.vertices {
+                if let Some(stroke) = v.stroke {
+                    let style = StrokeStyle {
+                        weight: v.stroke_weight,
+                        ..style
+                    };
+                    push_stroke(
+                        frame,
+                        gl_triangle::point(v.point(), style).flatten(),
+                        stroke,
+                    );
+                }
+            }
+            return;
+        }
+
+        // the outlines are stroked together, one shape for each color, so a
+        // translucent stroke is blended once along the edges they share
+        let mut strokes: Vec<(Color, Vec<Point>)> = Vec::new();
+        for (indices, closed) in self.outlines(close) {
+            self.stroke_outline(&indices, closed, style, &mut strokes);
+        }
+        for (color, points) in strokes {
+            push_stroke(frame, points, color);
+        }
+    }
+
+    /// Vertex indices of each outline the stroke follows, and whether it is closed
+    fn outlines(&self, close: CloseMode) -> Vec<(Vec<usize>, bool)> {
+        let n = self.vertices.len();
+        let chunks = |size: usize, closed: bool| -> Vec<(Vec<usize>, bool)> {
+            (0..n / size)
+                .map(|i| ((i * size..(i + 1) * size).collect(), closed))
+                .collect()
+        };
+
+        match self.kind {
+            GeometryKind::Points => Vec::new(),
+            GeometryKind::Lines => chunks(2, false),
+            GeometryKind::Triangles => chunks(3, true),
+            GeometryKind::Quads => chunks(4, true),
+            GeometryKind::TriangleStrip => (2..n).map(|i| (vec![i - 2, i - 1, i], true)).collect(),
+            GeometryKind::TriangleFan => (2..n).map(|i| (vec![0, i - 1, i], true)).collect(),
+            GeometryKind::QuadStrip => (3..n)
+                .step_by(2)
+                .map(|i| (vec![i - 3, i - 2, i, i - 1], true))
+                .collect(),
+            GeometryKind::Polygon => self
+                .contours()
+                .into_iter()
+                .enumerate()
+                .map(|(i, range)| {
+                    // holes are always closed, the outline only when asked to
+                    let closed = i > 0 || close == CloseMode::Close;
+                    (range.collect(), closed)
+                })
+                .collect(),
+        }
+    }
+
+    /// Strokes the outline through `indices`, adding its triangles to those
+    /// of `strokes` in the same color. Every segment takes the stroke color
+    /// and weight of the vertex it starts from, so the outline is split into
+    /// runs of segments sharing the same stroke.
+    fn stroke_outline(
+        &self,
+        indices: &[usize],
+        closed: bool,
+        style: StrokeStyle,
+        strokes: &mut Vec<(Color, Vec<Point>)>,
+    ) {
+        let segments = if closed {
+            indices.len()
+        } else {
+            indices.len().saturating_sub(1)
+        };
+
+        let mut runs: Vec<(Option<Color>, f32, Vec<Point>)> = Vec::new();
+        for k in 0..segments {
+            let a = &self.vertices[indices[k]];
+            let b = &self.vertices[indices[(k + 1) % indices.len()]];
+            match runs.last_mut() {
+                Some((stroke, weight, points))
+                    if *stroke == a.stroke && *weight == a.stroke_weight =>
+                {
+                    points.push(b.point());
+                }
+                _ => runs.push((a.stroke, a.stroke_weight, vec![a.point(), b.point()])),
+            }
+        }
+
+        // a closed outline drawn in one stroke gets a join instead of two caps
+        let closed = closed && runs.len() == 1;
+        for (stroke, weight, points) in runs {
+            if let Some(stroke) = stroke {
+                let style = StrokeStyle { weight, ..style };
+                let triangles = stroke::polyline(&points, closed, style);
+                match strokes.iter_mut().find(|(color, _)| *color == stroke) {
+                    Some((_, points)) => points.extend(triangles),
+                    None => strokes.push((stroke, triangles.into_iter().collect())),
+                }
+            }
+        }
+    }
+}
+
+fn push_stroke(frame: &mut GlFrame, points: impl IntoIterator<Item = Point>, color: Color) {
+    let vertices = points.into_iter().map(|p| vert2d(p.x, p.y, color));
+    frame.push_stroke(vertices, color);
 }
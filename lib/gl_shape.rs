@@ -1,33 +1,276 @@
+use std::{ops::Range, sync::OnceLock, thread};
+
 use glium::index;
 
-use crate::core::vertex::Vertex;
+use crate::{
+    core::vertex::{Instance, SdfVertex, Vertex},
+    Color,
+};
 
+/// A shape drawn from a range of the vertices of its frame
+#[derive(Debug, Clone)]
 pub struct GlShape {
-    pub vertices: Vec<Vertex>,
+    pub range: Range<usize>,
     pub index_type: index::PrimitiveType,
+    /// Blend each pixel at most once, even where the primitives overlap
+    pub cover_once: bool,
+    /// Draw the vertices once for each of this range of the instances of the
+    /// frame, instead of once as they are
+    pub instances: Option<Range<usize>>,
+    /// The range is in the SDF vertices of the frame, which draw shapes from
+    /// their signed distance instead of their triangles
+    pub sdf: bool,
 }
 
-pub struct LazyGlShape {
-    shape: Box<dyn Iterator<Item = Vertex>>,
-    index_type: index::PrimitiveType,
+/// The shapes of a frame, tessellated one after the other into a single
+/// vertex arena that is reused from frame to frame.
+///
+/// Points, ellipses, triangles and `begin_shape` fills other than polygons
+/// are written straight into it. Strokes, polygon fills and the other
+/// primitives are still built in a vector of their own and copied in.
+#[derive(Debug, Default)]
+pub struct GlFrame {
+    pub vertices: Vec<Vertex>,
+    pub instances: Vec<Instance>,
+    pub sdf_vertices: Vec<SdfVertex>,
+    pub shapes: Vec<GlShape>,
 }
 
-impl LazyGlShape {
-    pub fn new(
-        shape: impl Iterator<Item = Vertex> + 'static,
+impl GlFrame {
+    /// Appends a shape made of `vertices`.
+    pub fn push(
+        &mut self,
+        vertices: impl IntoIterator<Item = Vertex>,
         index_type: index::PrimitiveType,
-    ) -> LazyGlShape {
-        LazyGlShape {
-            shape: Box::new(shape),
+    ) {
+        self.push_shape(vertices, index_type, false);
+    }
+
+    /// Appends a stroke drawn in `color`.
+    ///
+    /// Stroke tessellation overlaps itself at joins, caps and crossings, which
+    /// shows as darker spots when the color is translucent, so the backend is
+    /// then asked to blend each pixel only once. Blending an opaque color
+    /// again changes nothing, and those strokes are left free to be drawn
+    /// together with their neighbors.
+    pub fn push_stroke(&mut self, vertices: impl IntoIterator<Item = Vertex>, color: Color) {
+        let cover_once = color.alpha < u8::MAX;
+        self.push_shape(vertices, index::PrimitiveType::TrianglesList, cover_once);
+    }
+
+    /// Appends a mesh drawn once for each of `instances`, which place and
+    /// color it. The colors of the mesh are multiplied by the color of the
+    /// instance, so a white mesh takes the instance color.
+    pub fn push_instanced(
+        &mut self,
+        mesh: impl IntoIterator<Item = Vertex>,
+        instances: impl IntoIterator<Item = Instance>,
+    ) {
+        let start = self.instances.len();
+        self.instances.extend(instances);
+        self.push_shape(mesh, index::PrimitiveType::TrianglesList, false);
+        if let Some(shape) = self.shapes.last_mut() {
+            shape.instances = Some(start..self.instances.len());
+        }
+    }
+
+    /// Appends shapes drawn from their signed distance, each by its own quad.
+    pub fn push_sdf(&mut self, vertices: impl IntoIterator<Item = SdfVertex>) {
+        let start = self.sdf_vertices.len();
+        self.sdf_vertices.extend(vertices);
+        self.shapes.push(GlShape {
+            range: start..self.sdf_vertices.len(),
+            index_type: index::PrimitiveType::TrianglesList,
+            cover_once: false,
+            instances: None,
+            sdf: true,
+        });
+    }
+
+    fn push_shape(
+        &mut self,
+        vertices: impl IntoIterator<Item = Vertex>,
+        index_type: index::PrimitiveType,
+        cover_once: bool,
+    ) {
+        let start = self.vertices.len();
+        self.vertices.extend(vertices);
+        self.shapes.push(GlShape {
+            range: start..self.vertices.len(),
             index_type,
+            cover_once,
+            instances: None,
+            sdf: false,
+        });
+    }
+
+    /// Moves the shapes of each of `parts` in turn after the shapes of this
+    /// frame, making room for all of them at once. The parts are left empty,
+    /// with their memory kept for the next frame.
+    fn append(&mut self, parts: &mut [GlFrame]) {
+        let total = |len: fn(&GlFrame) -> usize| parts.iter().map(len).sum();
+        self.vertices.reserve(total(|part| part.vertices.len()));
+        self.instances.reserve(total(|part| part.instances.len()));
+        self.sdf_vertices
+            .reserve(total(|part| part.sdf_vertices.len()));
+        self.shapes.reserve(total(|part| part.shapes.len()));
+
+        let shift = |range: Range<usize>, offset: usize| range.start + offset..range.end + offset;
+        for part in parts {
+            let offset = self.vertices.len();
+            let instance_offset = self.instances.len();
+            let sdf_offset = self.sdf_vertices.len();
+            self.vertices.append(&mut part.vertices);
+            self.instances.append(&mut part.instances);
+            self.sdf_vertices.append(&mut part.sdf_vertices);
+            self.shapes
+                .extend(part.shapes.drain(..).map(|shape| GlShape {
+                    range: shift(shape.range, if shape.sdf { sdf_offset } else { offset }),
+                    instances: shape.instances.map(|range| shift(range, instance_offset)),
+                    ..shape
+                }));
         }
     }
 
-    pub fn run(self) -> GlShape {
-        let vertices = self.shape.collect();
-        GlShape {
-            vertices,
-            index_type: self.index_type,
+    /// The vertices of `shape`, one of the shapes of this frame
+    pub fn vertices(&self, shape: &GlShape) -> &[Vertex] {
+        &self.vertices[shape.range.clone()]
+    }
+
+    /// The vertices in `range`, repeated for each of the instances in
+    /// `instances` and placed by it, for backends drawing them one by one
+    pub fn instanced_vertices(&self, range: Range<usize>, instances: Range<usize>) -> Vec<Vertex> {
+        let mesh = &self.vertices[range];
+        self.instances[instances]
+            .iter()
+            .flat_map(|instance| mesh.iter().map(|vertex| instance.apply(vertex)))
+            .collect()
+    }
+
+    /// Removes every shape, keeping the memory for the next frame.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.instances.clear();
+        self.sdf_vertices.clear();
+        self.shapes.clear();
+    }
+}
+
+/// A shape waiting to be tessellated into a frame, on whichever thread the
+/// queue hands it to
+pub trait Job: Send {
+    fn tessellate(self, frame: &mut GlFrame);
+}
+
+/// Queues below this length are not worth spreading across threads
+const PARALLEL_JOBS: usize = 256;
+
+/// The shapes submitted during a frame, and the frame they are tessellated into.
+///
+/// Shapes are tessellated as soon as they are pushed, until the frame has
+/// enough of them to be worth spreading across threads. From then on they
+/// wait until the frame is drawn, to be tessellated on several threads at
+/// once.
+pub struct ShapeQueue<J> {
+    jobs: Vec<J>,
+    frame: GlFrame,
+    /// Frames the other threads tessellate into, kept to reuse their memory
+    parts: Vec<GlFrame>,
+    /// Shapes pushed since the frame was last taken
+    pushed: usize,
+    /// Threads to tessellate on, all the available cores when `None`. Only
+    /// the calling thread by default, until spreading frames has been shown
+    /// to pay off on machines with several cores.
+    threads: Option<usize>,
+}
+
+impl<J> Default for ShapeQueue<J> {
+    fn default() -> Self {
+        ShapeQueue {
+            jobs: Vec::new(),
+            frame: GlFrame::default(),
+            parts: Vec::new(),
+            pushed: 0,
+            threads: Some(1),
+        }
+    }
+}
+
+impl<J: Job> ShapeQueue<J> {
+    pub fn push(&mut self, job: J) {
+        // once a job waits, the ones after it have to wait too to keep their order
+        if self.jobs.is_empty() && (self.pushed < PARALLEL_JOBS || self.threads() == 1) {
+            job.tessellate(&mut self.frame);
+        } else {
+            self.jobs.push(job);
+        }
+        self.pushed += 1;
+    }
+
+    /// Drops every shape submitted so far.
+    pub fn clear(&mut self) {
+        self.jobs.clear();
+        self.frame.clear();
+        self.pushed = 0;
+    }
+
+    pub fn set_threads(&mut self, threads: Option<usize>) {
+        self.threads = threads;
+    }
+
+    fn threads(&self) -> usize {
+        static CORES: OnceLock<usize> = OnceLock::new();
+        let cores =
+            || *CORES.get_or_init(|| thread::available_parallelism().map_or(1, |n| n.get()));
+        self.threads.unwrap_or_else(cores).max(1)
+    }
+
+    /// The frame with every submitted shape tessellated, in the order they
+    /// were pushed whatever the number of threads. It is cleared once drawn.
+    ///
+    /// The waiting shapes are cut into one run of consecutive shapes per
+    /// thread. The first run is tessellated straight into the frame on this
+    /// thread while the others fill their own parts, appended to it in turn.
+    pub fn frame(&mut self) -> &mut GlFrame {
+        self.pushed = 0;
+        let threads = self.threads();
+        if threads == 1 || self.jobs.len() < PARALLEL_JOBS {
+            self.jobs
+                .drain(..)
+                .for_each(|job| job.tessellate(&mut self.frame));
+            return &mut self.frame;
+        }
+
+        // the runs are split off the end, so that each job is moved only once
+        // and the first one stays where it is
+        let run = self.jobs.len().div_ceil(threads);
+        let mut runs = Vec::with_capacity(threads);
+        while self.jobs.len() > run {
+            let start = (self.jobs.len() - 1) / run * run;
+            runs.push(self.jobs.split_off(start));
         }
+        runs.reverse();
+        self.parts.resize_with(runs.len(), GlFrame::default);
+        let first = self.jobs.drain(..);
+        let frame = &mut self.frame;
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = runs
+                .into_iter()
+                .zip(&mut self.parts)
+                .map(|(run, part)| {
+                    scope.spawn(move || run.into_iter().for_each(|job| job.tessellate(part)))
+                })
+                .collect();
+
+            first.for_each(|job| job.tessellate(frame));
+            for handle in handles {
+                if let Err(panic) = handle.join() {
+                    std::panic::resume_unwind(panic);
+                }
+            }
+        });
+        self.frame.append(&mut self.parts);
+        &mut self.frame
     }
 }
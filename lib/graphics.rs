@@ -1,153 +1,929 @@
 use glium::index::PrimitiveType;
 
 use crate::{
-    geometry::{Geometry, GeometryKind, GeometryVertex},
-    gl_shape::LazyGlShape,
-    primitives::shapes_2d::{Ellipse, EllipseArc, Line, Point, Quad, Triangle},
-    settings::{StrokeJoin, StrokeSettings},
+    core::vertex::{vert2d, Instance, SdfVertex, Vertex},
+    geometry::{CloseMode, Geometry, GeometryError, GeometryKind, GeometryVertex},
+    gl_shape::{self, GlFrame, ShapeQueue},
+    primitives::shapes_2d::{
+        Bezier, CornerRadii, Ellipse, EllipseArc, Line, Point, Polyline, Quad, RegularPolygon,
+        RoundedRect, Star, Triangle, CURVE_TOLERANCE,
+    },
+    settings::{
+        ArcMode, EllipseDetail, FillRule, ShapeMode, StrokeJoin, StrokeSettings, StrokeStyle,
+    },
+    tess::{fns::gl_triangle, sdf::SdfShape, tessellate::Tessellate},
     traits::{BeginShape, Renderer, Stroke},
-    Color, StrokeCap,
+    utils, Color, Matrix2D, Path, StrokeCap,
 };
 
 #[derive(Default)]
 pub struct GraphicsP2D {
     stroke_settings: StrokeSettings,
+    style_stack: Vec<StrokeSettings>,
+
+    matrix: Matrix2D,
+    matrix_stack: Vec<Matrix2D>,
+
+    curve_tightness: f32,
+    /// Segments per ellipse set by the user, instead of one from its size
+    ellipse_detail: Option<usize>,
 
     geometry: Option<Geometry>,
-    shapes: Vec<LazyGlShape>,
+    queue: ShapeQueue<Job>,
+    /// The last fills drawn, if they all were copies of the same mesh
+    run: Option<InstanceRun>,
+    /// Draw the shapes that can be from their signed distance
+    sdf: bool,
 }
 
 impl GraphicsP2D {
-    pub fn background(&mut self, color: Color, width: u32, height: u32) {
-        self.shapes.clear();
+    /// Queues a shape to tessellate, after the fills of the current run.
+    fn push_job(&mut self, job: Job) {
+        self.flush_run();
+        self.queue.push(job);
+    }
+
+    /// Queues a fill of `mesh` placed by `transform`, then by the current
+    /// matrix. Consecutive fills of the same mesh are gathered into a run,
+    /// drawn with instancing if it grows long enough.
+    fn push_instance(&mut self, mesh: Mesh, transform: Matrix2D, color: Color) {
+        let instance = Instance::new(self.matrix * transform, color);
+        let shared = self
+            .run
+            .as_mut()
+            .and_then(|run| run.mesh.share(mesh).map(|shared| (run, shared)));
+        match shared {
+            Some((run, shared)) => {
+                run.mesh = shared;
+                run.instances.push(instance);
+            }
+            None => {
+                self.flush_run();
+                self.run = Some(InstanceRun {
+                    mesh,
+                    instances: vec![instance],
+                });
+            }
+        }
+    }
+
+    /// Queues the fills of the current run, if any.
+    fn flush_run(&mut self) {
+        if let Some(run) = self.run.take() {
+            self.queue.push(Job::Instances(run));
+        }
+    }
+
+    /// Queues `shape` placed by `placement`, then by the current matrix, to be
+    /// drawn from its signed distance: filled with `color`, or with its
+    /// outline stroked in it `weight` wide when `weight` is positive.
+    fn push_sdf(&mut self, shape: SdfShape, placement: Matrix2D, color: Color, weight: f32) {
+        // the edge of a shape without area would still be faded in
+        if weight <= 0.0 && shape.is_empty() {
+            return;
+        }
+        self.push_job(Job::Sdf {
+            shape,
+            matrix: self.matrix * placement,
+            color,
+            weight,
+        });
+    }
+
+    /// The color of outlines drawn from their signed distance, if they are
+    /// wide enough to be seen
+    fn sdf_stroke(&self) -> Option<Color> {
+        let weight = self.stroke_settings.stroke_weight;
+        self.stroke_settings.stroke.filter(|_| weight > 0.0)
+    }
+
+    /// Whether points and lines are drawn from their signed distance
+    fn sdf_caps(&self) -> bool {
+        self.sdf && self.stroke_settings.stroke_cap == StrokeCap::Round
+    }
+
+    /// Queues a fill, transformed by the current matrix.
+    fn push_shape(&mut self, shape: FillShape, color: Color) {
+        self.push_job(Job::Fill {
+            shape,
+            matrix: self.matrix,
+            color,
+        });
+    }
+
+    /// Queues a stroke in the current style, transformed by the current matrix.
+    fn push_stroke(&mut self, shape: StrokeShape, color: Color) {
+        self.push_job(Job::Stroke {
+            shape,
+            style: self.stroke_style(),
+            matrix: self.matrix,
+            color,
+        });
+    }
 
-        let rect = Quad::rect(0.0, 0.0, width as f32, height as f32)
-            .tessellate_fill()
-            .color(color);
+    pub fn background(&mut self, color: Color, width: u32, height: u32) {
+        // an opaque background hides everything queued before it, a translucent
+        // one has to be blended over it (e.g. to fade out previous frames)
+        if color.alpha == u8::MAX {
+            self.run = None;
+            self.queue.clear();
+        }
 
-        self.shapes
-            .push(LazyGlShape::new(rect, PrimitiveType::TrianglesList));
+        // the background always covers the whole canvas, whatever the current matrix
+        self.push_job(Job::Fill {
+            shape: FillShape::Quad(Quad::rect(0.0, 0.0, width as f32, height as f32)),
+            matrix: Matrix2D::IDENTITY,
+            color,
+        });
     }
 
     pub fn point(&mut self, vertex: (f32, f32)) {
         if let Some(stroke) = self.stroke_settings.stroke {
-            let point = Point::new(vertex)
-                .tessellate_fill(
-                    self.stroke_settings.stroke_weight,
-                    self.stroke_settings.stroke_cap,
-                )
-                .color(stroke);
-            self.shapes
-                .push(LazyGlShape::new(point, PrimitiveType::TrianglesList));
+            if self.sdf_caps() {
+                let half = self.stroke_settings.stroke_weight / 2.0;
+                let placement = Matrix2D::translation(vertex.0, vertex.1);
+                self.push_sdf(SdfShape::ellipse((half, half)), placement, stroke, 0.0);
+                return;
+            }
+            self.push_stroke(StrokeShape::Point(Point::new(vertex)), stroke);
         }
     }
 
     pub fn line(&mut self, a: (f32, f32), b: (f32, f32)) {
         if let Some(stroke) = self.stroke_settings.stroke {
-            let line = Line::new(a, b)
-                .tessellate_fill(
-                    self.stroke_settings.stroke_weight,
-                    self.stroke_settings.stroke_cap,
-                )
-                .color(stroke);
-            self.shapes
-                .push(LazyGlShape::new(line, PrimitiveType::TrianglesList));
+            if self.sdf_caps() {
+                let (line, placement) = SdfShape::line(a, b, self.stroke_settings.stroke_weight);
+                self.push_sdf(line, placement, stroke, 0.0);
+                return;
+            }
+            self.push_stroke(StrokeShape::Line(Line::new(a, b)), stroke);
+        }
+    }
+
+    /// Draws a point at each of `points`, in the stroke color or, when given,
+    /// in the color of the same index in `colors`.
+    ///
+    /// The points are instances of a single dot, placed and colored by the
+    /// backend.
+    pub fn points(&mut self, points: &[(f32, f32)], colors: Option<&[Color]>) {
+        let Some(colors) = self.bulk_colors(points.len(), colors) else {
+            return;
+        };
+        let matrix = self.matrix;
+        let half = self.stroke_settings.stroke_weight / 2.0;
+        if self.sdf_caps() {
+            let dot = SdfShape::ellipse((half, half));
+            // like `push_sdf`, dots without area are not drawn at all
+            if !dot.is_empty() {
+                let quads = points.iter().enumerate().flat_map(|(i, &(x, y))| {
+                    dot.quad(matrix * Matrix2D::translation(x, y), colors.get(i), 0.0)
+                });
+                self.push_job(Job::SdfQuads(quads.collect()));
+            }
+            return;
+        }
+        let (mesh, transform) = match self.stroke_settings.stroke_cap {
+            StrokeCap::Butt => return,
+            StrokeCap::Round => {
+                let segments = self.stroke_style().detail.segments(half);
+                (Mesh::ellipse(segments), Matrix2D::scaling(half, half))
+            }
+            StrokeCap::Square => (
+                Mesh::Rect,
+                Matrix2D::translation(-half, -half) * Matrix2D::scaling(2.0 * half, 2.0 * half),
+            ),
+        };
+        // the dot around the origin, only turned and scaled by the matrix
+        let dot = Matrix2D {
+            m02: 0.0,
+            m12: 0.0,
+            ..matrix
+        } * transform;
+        let instances = points
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| {
+                let (x, y) = matrix.apply_xy(x, y);
+                Instance::new(Matrix2D::translation(x, y) * dot, colors.get(i))
+            })
+            .collect();
+        self.push_job(Job::Instances(InstanceRun { mesh, instances }));
+    }
+
+    /// Draws a line between the two ends of each of `lines`, in the stroke
+    /// color or, when given, in the color of the same index in `colors`. They
+    /// are drawn as one shape, or as instances of a single rectangle when
+    /// their caps are not round.
+    pub fn lines(&mut self, lines: &[[(f32, f32); 2]], colors: Option<&[Color]>) {
+        let Some(colors) = self.bulk_colors(lines.len(), colors) else {
+            return;
+        };
+        let matrix = self.matrix;
+        let weight = self.stroke_settings.stroke_weight;
+        if self.sdf_caps() {
+            // like `push_sdf`, lines without width are not drawn at all
+            if weight > 0.0 {
+                let quads = lines.iter().enumerate().flat_map(|(i, &[a, b])| {
+                    let (line, placement) = SdfShape::line(a, b, weight);
+                    line.quad(matrix * placement, colors.get(i), 0.0)
+                });
+                self.push_job(Job::SdfQuads(quads.collect()));
+            }
+            return;
+        }
+        // how far each line reaches past its ends
+        let cap = match self.stroke_settings.stroke_cap {
+            StrokeCap::Butt => 0.0,
+            StrokeCap::Square => weight / 2.0,
+            StrokeCap::Round => {
+                let style = self.stroke_style();
+                let triangles = lines.iter().enumerate().flat_map(|(i, &[a, b])| {
+                    let color = colors.get(i);
+                    gl_triangle::line(Point::new(a), Point::new(b), style)
+                        .into_iter()
+                        .map(move |point| {
+                            let (x, y) = matrix.apply_xy(point.x, point.y);
+                            vert2d(x, y, color)
+                        })
+                });
+                self.push_job(Job::Triangles(triangles.collect()));
+                return;
+            }
+        };
+        let instances = lines
+            .iter()
+            .enumerate()
+            .map(|(i, &[a, b])| {
+                let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+                // the unit square, stretched along the line and across it
+                let transform = Matrix2D::translation(a.0, a.1)
+                    * Matrix2D::rotation(dy.atan2(dx))
+                    * Matrix2D::translation(-cap, -weight / 2.0)
+                    * Matrix2D::scaling(dx.hypot(dy) + 2.0 * cap, weight);
+                Instance::new(matrix * transform, colors.get(i))
+            })
+            .collect();
+        self.push_job(Job::Instances(InstanceRun {
+            mesh: Mesh::Rect,
+            instances,
+        }));
+    }
+
+    /// Strokes the open polyline through `points`, with joins between its
+    /// segments and caps at its ends.
+    pub fn polyline(&mut self, points: &[(f32, f32)]) {
+        if let Some(stroke) = self.stroke_settings.stroke {
+            self.push_stroke(StrokeShape::Polyline(Polyline::new(points)), stroke);
+        }
+    }
+
+    /// The colors of the `len` elements of a bulk draw, or `None` when there
+    /// is nothing to draw them with.
+    fn bulk_colors<'a>(&self, len: usize, colors: Option<&'a [Color]>) -> Option<BulkColors<'a>> {
+        match colors {
+            Some(colors) => {
+                assert_eq!(colors.len(), len, "expected one color per element");
+                Some(BulkColors::Each(colors))
+            }
+            None => self.stroke_settings.stroke.map(BulkColors::Same),
         }
     }
 
     pub fn triangle(&mut self, a: (f32, f32), b: (f32, f32), c: (f32, f32)) {
         if let Some(fill) = self.stroke_settings.fill {
-            let triangle = Triangle::new(a, b, c).tessellate_fill().color(fill);
-            self.shapes
-                .push(LazyGlShape::new(triangle, PrimitiveType::TrianglesList));
+            self.push_shape(FillShape::Triangle(Triangle::new(a, b, c)), fill);
         }
         if let Some(stroke) = self.stroke_settings.stroke {
-            let triangle = Triangle::new(a, b, c)
-                .tessellate_stroke(self.stroke_settings.stroke_weight)
-                .color(stroke);
-            self.shapes
-                .push(LazyGlShape::new(triangle, PrimitiveType::TrianglesList));
+            self.push_stroke(StrokeShape::Triangle(Triangle::new(a, b, c)), stroke);
         }
     }
 
-    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+    /// Draws a rectangle, placed according to the rect mode.
+    pub fn rect(&mut self, a: f32, b: f32, c: f32, d: f32) {
+        let (x, y, width, height) = self.stroke_settings.rect_mode.bounds(a, b, c, d);
+        match (self.stroke_settings.fill, self.stroke_settings.stroke) {
+            (Some(fill), None) => {
+                let transform = Matrix2D::translation(x, y) * Matrix2D::scaling(width, height);
+                self.push_instance(Mesh::Rect, transform, fill);
+            }
+            (Some(fill), Some(_)) => {
+                self.push_shape(FillShape::Quad(Quad::rect(x, y, width, height)), fill);
+            }
+            (None, _) => {}
+        }
+        if let Some(stroke) = self.stroke_settings.stroke {
+            self.push_stroke(StrokeShape::Quad(Quad::rect(x, y, width, height)), stroke);
+        }
+    }
+
+    pub fn quad(&mut self, a: (f32, f32), b: (f32, f32), c: (f32, f32), d: (f32, f32)) {
         if let Some(fill) = self.stroke_settings.fill {
-            let rect = Quad::rect(x, y, width, height)
-                .tessellate_fill()
-                .color(fill);
-            self.shapes
-                .push(LazyGlShape::new(rect, PrimitiveType::TrianglesList));
+            self.push_shape(FillShape::Quad(Quad::new(a, b, c, d)), fill);
         }
         if let Some(stroke) = self.stroke_settings.stroke {
-            let rect = Quad::rect(x, y, width, height)
-                .tessellate_stroke(self.stroke_settings.stroke_weight)
-                .color(stroke);
-            self.shapes
-                .push(LazyGlShape::new(rect, PrimitiveType::TrianglesList));
+            self.push_stroke(StrokeShape::Quad(Quad::new(a, b, c, d)), stroke);
         }
     }
 
-    pub fn ellipse(&mut self, x: f32, y: f32, width: f32, height: f32) {
+    pub fn polygon(&mut self, center: (f32, f32), radius: f32, sides: usize, rotation: f32) {
         if let Some(fill) = self.stroke_settings.fill {
-            let ellipse = Ellipse::new((x, y), (width, height))
-                .tessellate_fill(20)
-                .color(fill);
-            self.shapes
-                .push(LazyGlShape::new(ellipse, PrimitiveType::TrianglesList));
+            let polygon = RegularPolygon::new(center, radius, sides, rotation);
+            self.push_shape(FillShape::Polygon(polygon), fill);
         }
         if let Some(stroke) = self.stroke_settings.stroke {
-            let ellipse = Ellipse::new((x, y), (width, height))
-                .tessellate_stroke(self.stroke_settings.stroke_weight, 20)
-                .color(stroke);
-            self.shapes
-                .push(LazyGlShape::new(ellipse, PrimitiveType::TrianglesList));
+            let polygon = RegularPolygon::new(center, radius, sides, rotation);
+            self.push_stroke(StrokeShape::Polygon(polygon), stroke);
         }
     }
 
-    pub fn ellipse_arc(&mut self, x: f32, y: f32, width: f32, height: f32, start: f32, stop: f32) {
+    pub fn star(&mut self, center: (f32, f32), inner: f32, outer: f32, points: usize) {
         if let Some(fill) = self.stroke_settings.fill {
-            let arc = EllipseArc::new((x, y), (width, height), start, stop)
-                .tessellate_fill(20)
-                .color(fill);
-            self.shapes
-                .push(LazyGlShape::new(arc, PrimitiveType::TrianglesList));
+            let star = Star::new(center, inner, outer, points);
+            self.push_shape(FillShape::Star(star), fill);
         }
         if let Some(stroke) = self.stroke_settings.stroke {
-            let arc = EllipseArc::new((x, y), (width, height), start, stop)
-                .tessellate_stroke(self.stroke_settings.stroke_weight, 20)
-                .color(stroke);
-            self.shapes
-                .push(LazyGlShape::new(arc, PrimitiveType::TrianglesList));
+            let star = Star::new(center, inner, outer, points);
+            self.push_stroke(StrokeShape::Star(star), stroke);
         }
     }
 
-    pub fn circle(&mut self, x: f32, y: f32, diameter: f32) {
-        self.ellipse(x, y, diameter, diameter);
+    /// Draws a rectangle with rounded corners, placed according to the rect mode.
+    ///
+    /// Unlike circles and rects, a rounded rect cannot be scaled from a unit
+    /// mesh without stretching its corners, so only fills of the same size,
+    /// radii and detail are gathered into instanced runs. Rounded rects of
+    /// varying sizes are each tessellated on their own.
+    pub fn rounded_rect(&mut self, a: f32, b: f32, c: f32, d: f32, radii: CornerRadii) {
+        let (x, y, width, height) = self.stroke_settings.rect_mode.bounds(a, b, c, d);
+        if self.sdf {
+            self.sdf_rounded_rect(RoundedRect::new(x, y, width, height, radii));
+            return;
+        }
+        let detail = self.detail(self.curve_tolerance());
+        match (self.stroke_settings.fill, self.stroke_settings.stroke) {
+            (Some(fill), None) => {
+                let mesh = Mesh::RoundedRect {
+                    width,
+                    height,
+                    radii,
+                    detail,
+                };
+                self.push_instance(mesh, Matrix2D::translation(x, y), fill);
+            }
+            (Some(fill), Some(_)) => {
+                let rect = RoundedRect::new(x, y, width, height, radii);
+                self.push_shape(FillShape::RoundedRect(rect, detail), fill);
+            }
+            (None, _) => {}
+        }
+        if let Some(stroke) = self.stroke_settings.stroke {
+            let rect = RoundedRect::new(x, y, width, height, radii);
+            self.push_stroke(StrokeShape::RoundedRect(rect), stroke);
+        }
+    }
+
+    /// Draws `rect` from its signed distance.
+    fn sdf_rounded_rect(&mut self, rect: RoundedRect) {
+        let (min, max, radii) = (rect.min, rect.max, rect.radii);
+        let shape = SdfShape::RoundedBox {
+            half_size: ((max.x - min.x) / 2.0, (max.y - min.y) / 2.0),
+            radii,
+        };
+        let placement = Matrix2D::translation((min.x + max.x) / 2.0, (min.y + max.y) / 2.0);
+        if let Some(fill) = self.stroke_settings.fill {
+            self.push_sdf(shape, placement, fill, 0.0);
+        }
+        if let Some(stroke) = self.sdf_stroke() {
+            // the distance rounds the outer side of every corner
+            let round = [
+                radii.top_left,
+                radii.top_right,
+                radii.bottom_right,
+                radii.bottom_left,
+            ]
+            .iter()
+            .all(|&radius| radius > 0.0);
+            if round || self.stroke_settings.stroke_join == StrokeJoin::Round {
+                self.push_sdf(shape, placement, stroke, self.stroke_settings.stroke_weight);
+            } else {
+                self.push_stroke(StrokeShape::RoundedRect(rect), stroke);
+            }
+        }
+    }
+
+    /// Center and radii of the ellipse placed by `a`, `b`, `c` and `d`
+    /// according to the ellipse mode.
+    fn ellipse_axes(&self, a: f32, b: f32, c: f32, d: f32) -> ((f32, f32), (f32, f32)) {
+        let (x, y, width, height) = self.stroke_settings.ellipse_mode.bounds(a, b, c, d);
+        let axes = (width.abs() / 2.0, height.abs() / 2.0);
+        ((x + width / 2.0, y + height / 2.0), axes)
+    }
+
+    /// Draws an ellipse, placed according to the ellipse mode.
+    pub fn ellipse(&mut self, a: f32, b: f32, c: f32, d: f32) {
+        let (center, axes) = self.ellipse_axes(a, b, c, d);
+        if self.sdf {
+            let placement = Matrix2D::translation(center.0, center.1);
+            if let Some(fill) = self.stroke_settings.fill {
+                self.push_sdf(SdfShape::ellipse(axes), placement, fill, 0.0);
+            }
+            if let Some(stroke) = self.sdf_stroke() {
+                let weight = self.stroke_settings.stroke_weight;
+                self.push_sdf(SdfShape::ellipse(axes), placement, stroke, weight);
+            }
+            return;
+        }
+        let segments = self
+            .detail(self.curve_tolerance())
+            .segments(axes.0.abs().max(axes.1.abs()));
+        match (self.stroke_settings.fill, self.stroke_settings.stroke) {
+            (Some(fill), None) => {
+                let transform =
+                    Matrix2D::translation(center.0, center.1) * Matrix2D::scaling(axes.0, axes.1);
+                self.push_instance(Mesh::ellipse(segments), transform, fill);
+            }
+            (Some(fill), Some(_)) => {
+                let ellipse = Ellipse::new(center, axes);
+                self.push_shape(FillShape::Ellipse(ellipse, segments), fill);
+            }
+            (None, _) => {}
+        }
+        if let Some(stroke) = self.stroke_settings.stroke {
+            let ellipse = Ellipse::new(center, axes);
+            self.push_stroke(StrokeShape::Ellipse(ellipse, segments), stroke);
+        }
+    }
+
+    /// Draws the part of an ellipse from `start` to `stop` radians, the
+    /// ellipse being placed according to the ellipse mode.
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc(&mut self, a: f32, b: f32, c: f32, d: f32, start: f32, stop: f32, mode: ArcMode) {
+        let (center, axes) = self.ellipse_axes(a, b, c, d);
+        let radius = axes.0.abs().max(axes.1.abs());
+        let detail = self.detail(self.curve_tolerance());
+        if let Some(fill) = self.stroke_settings.fill {
+            let arc = EllipseArc::new(center, axes, start, stop, mode);
+            let segments = detail.arc_segments(radius, arc.sweep);
+            self.push_shape(FillShape::Arc(arc, segments), fill);
+        }
+        if let Some(stroke) = self.stroke_settings.stroke {
+            let arc = EllipseArc::new(center, axes, start, stop, mode);
+            let segments = detail.arc_segments(radius, arc.sweep);
+            self.push_stroke(StrokeShape::Arc(arc, segments), stroke);
+        }
     }
 
-    pub fn arc(&mut self, x: f32, y: f32, radius: f32, start: f32, stop: f32) {
-        self.ellipse_arc(x, y, radius, radius, start, stop);
+    pub fn circle(&mut self, x: f32, y: f32, diameter: f32) {
+        self.ellipse(x, y, diameter, diameter);
     }
 
     pub fn square(&mut self, x: f32, y: f32, size: f32) {
         self.rect(x, y, size, size);
     }
+
+    pub fn bezier(&mut self, a: (f32, f32), c1: (f32, f32), c2: (f32, f32), b: (f32, f32)) {
+        self.draw_curve(Bezier::new(a, c1, c2, b));
+    }
+
+    /// Draws the Catmull-Rom segment from `b` to `c`; `a` and `d` shape its ends.
+    pub fn curve(&mut self, a: (f32, f32), b: (f32, f32), c: (f32, f32), d: (f32, f32)) {
+        self.draw_curve(Bezier::curve(a, b, c, d, self.curve_tightness));
+    }
+
+    /// Fills and strokes `path`, transformed by the current matrix.
+    pub fn path(&mut self, path: &Path) {
+        let tolerance = self.curve_tolerance();
+        if let Some(fill) = self.stroke_settings.fill {
+            let fill_rule = self.stroke_settings.fill_rule;
+            self.push_shape(FillShape::Path(path.clone(), fill_rule, tolerance), fill);
+        }
+        if let Some(stroke) = self.stroke_settings.stroke {
+            self.push_stroke(StrokeShape::Path(path.clone(), tolerance), stroke);
+        }
+    }
+
+    fn draw_curve(&mut self, curve: Bezier) {
+        let tolerance = self.curve_tolerance();
+        if let Some(fill) = self.stroke_settings.fill {
+            let fill_rule = self.stroke_settings.fill_rule;
+            self.push_shape(FillShape::Curve(curve, fill_rule, tolerance), fill);
+        }
+        if let Some(stroke) = self.stroke_settings.stroke {
+            self.push_stroke(StrokeShape::Curve(curve, tolerance), stroke);
+        }
+    }
+
+    /// How finely to split ellipses, for a curve tolerance in the space
+    /// they are tessellated in.
+    fn detail(&self, tolerance: f32) -> EllipseDetail {
+        match self.ellipse_detail {
+            Some(segments) => EllipseDetail::Segments(segments),
+            None => EllipseDetail::Tolerance(tolerance),
+        }
+    }
+
+    /// The stroke style for shapes tessellated in user space
+    fn stroke_style(&self) -> StrokeStyle {
+        StrokeStyle {
+            detail: self.detail(self.curve_tolerance()),
+            ..self.stroke_settings.style()
+        }
+    }
+
+    /// Splits ellipses, arcs and round strokes into `segments` per full turn,
+    /// or into as many as their size on screen needs when `None`.
+    pub fn ellipse_detail(&mut self, segments: Option<usize>) {
+        self.ellipse_detail = segments;
+    }
+
+    /// Draws ellipses, rounded rectangles, and points and lines with round
+    /// caps from their signed distance when `enabled`: each is a quad whose
+    /// pixels are shaded from how far they are from its outline, which makes
+    /// for smooth, anti-aliased edges.
+    pub fn sdf_shapes(&mut self, enabled: bool) {
+        self.sdf = enabled;
+    }
+
+    /// Tessellates the shapes of each frame on `threads` threads, or on as
+    /// many as there are cores when `None`. Defaults to one.
+    pub fn tessellation_threads(&mut self, threads: Option<usize>) {
+        self.queue.set_threads(threads);
+    }
+
+    /// How far, in user space, a flattened curve may stray from the real one
+    /// so that it stays within `CURVE_TOLERANCE` pixels once transformed.
+    fn curve_tolerance(&self) -> f32 {
+        let scale = self.matrix.determinant().abs().sqrt();
+        if scale.is_normal() {
+            CURVE_TOLERANCE / scale
+        } else {
+            CURVE_TOLERANCE
+        }
+    }
+
+    pub fn curve_tightness(&mut self, tightness: f32) {
+        self.curve_tightness = tightness;
+    }
+
+    /// Coordinate at `t` on the curve segment from `b` to `c`, with the current tightness.
+    pub fn curve_point(&self, a: f32, b: f32, c: f32, d: f32, t: f32) -> f32 {
+        utils::curve_point(a, b, c, d, t, self.curve_tightness)
+    }
+
+    pub fn curve_tangent(&self, a: f32, b: f32, c: f32, d: f32, t: f32) -> f32 {
+        utils::curve_tangent(a, b, c, d, t, self.curve_tightness)
+    }
 }
 
-impl BeginShape for GraphicsP2D {
-    type Item = (f32, f32);
+/// Runs of fills shorter than this are not worth instancing
+const MIN_INSTANCES: usize = 64;
 
-    fn begin_shape(&mut self, kind: GeometryKind) {
-        if self.geometry.is_some() {
-            panic!("begin_shape() has already been called");
+/// A shape whose fills only differ by where they are placed, which can
+/// therefore be drawn as instances of a single mesh
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mesh {
+    /// The unit circle split into `segments`, for circles that each need at
+    /// least `fewest`
+    Ellipse { segments: usize, fewest: usize },
+    /// The unit square, from the origin
+    Rect,
+    /// A rounded rectangle from the origin, only shared by rounded rects of
+    /// exactly its size, radii and detail
+    RoundedRect {
+        width: f32,
+        height: f32,
+        radii: CornerRadii,
+        detail: EllipseDetail,
+    },
+}
+
+impl Mesh {
+    /// The unit circle, split into `segments`
+    fn ellipse(segments: usize) -> Mesh {
+        Mesh::Ellipse {
+            segments,
+            fewest: segments,
         }
-        self.geometry = Some(Geometry::new(kind));
     }
 
-    fn vertex(&mut self, vertex: Self::Item) {
+    /// A mesh good enough for the fills of both meshes, if there is one.
+    /// Ellipses share the unit circle, split as finely as either needs, as
+    /// long as that is not much finer than any of them needs: a small dot
+    /// after a large circle would otherwise take many times its own vertices.
+    fn share(self, other: Mesh) -> Option<Mesh> {
+        match (self, other) {
+            (
+                Mesh::Ellipse { segments, fewest },
+                Mesh::Ellipse {
+                    segments: other,
+                    fewest: other_fewest,
+                },
+            ) => {
+                let segments = segments.max(other);
+                let fewest = fewest.min(other_fewest);
+                (segments <= fewest + fewest / 4).then_some(Mesh::Ellipse { segments, fewest })
+            }
+            _ => (self == other).then_some(self),
+        }
+    }
+
+    /// The triangles of the mesh, white to take the color of each instance
+    fn vertices(self) -> Vec<Vertex> {
+        match self {
+            Mesh::Ellipse { segments, .. } => Ellipse::new((0.0, 0.0), (1.0, 1.0))
+                .tessellate_fill(segments)
+                .color(Color::WHITE)
+                .collect(),
+            Mesh::Rect => Quad::rect(0.0, 0.0, 1.0, 1.0)
+                .tessellate_fill()
+                .color(Color::WHITE)
+                .collect(),
+            Mesh::RoundedRect {
+                width,
+                height,
+                radii,
+                detail,
+            } => RoundedRect::new(0.0, 0.0, width, height, radii)
+                .tessellate_fill(detail)
+                .color(Color::WHITE)
+                .collect(),
+        }
+    }
+}
+
+/// Consecutive fills of the same mesh: circles needing about as many
+/// segments, any rects, or rounded rects all alike
+struct InstanceRun {
+    mesh: Mesh,
+    instances: Vec<Instance>,
+}
+
+impl InstanceRun {
+    fn tessellate(self, frame: &mut GlFrame) {
+        let mesh = self.mesh.vertices();
+        if self.instances.len() >= MIN_INSTANCES {
+            frame.push_instanced(mesh, self.instances);
+        } else {
+            let vertices = self
+                .instances
+                .iter()
+                .flat_map(|instance| mesh.iter().map(|vertex| instance.apply(vertex)));
+            frame.push(vertices, PrimitiveType::TrianglesList);
+        }
+    }
+}
+
+/// The colors of the elements drawn by a bulk call
+enum BulkColors<'a> {
+    Same(Color),
+    Each(&'a [Color]),
+}
+
+impl BulkColors<'_> {
+    fn get(&self, index: usize) -> Color {
+        match self {
+            BulkColors::Same(color) => *color,
+            BulkColors::Each(colors) => colors[index],
+        }
+    }
+}
+
+/// A shape queued to be tessellated, recorded as the parameters it was drawn
+/// with, so that it can be handed to another thread as it is
+enum Job {
+    Fill {
+        shape: FillShape,
+        matrix: Matrix2D,
+        color: Color,
+    },
+    Stroke {
+        shape: StrokeShape,
+        style: StrokeStyle,
+        matrix: Matrix2D,
+        color: Color,
+    },
+    /// A shape drawn from its signed distance, stroked when `weight` is positive
+    Sdf {
+        shape: SdfShape,
+        matrix: Matrix2D,
+        color: Color,
+        weight: f32,
+    },
+    Instances(InstanceRun),
+    Geometry {
+        geometry: Geometry,
+        rule: FillRule,
+        style: StrokeStyle,
+        close: CloseMode,
+    },
+    /// Lines already tessellated and placed, each made of triangles that do
+    /// not overlap each other
+    Triangles(Vec<Vertex>),
+    /// Quads of shapes drawn from their signed distance, already placed
+    SdfQuads(Vec<SdfVertex>),
+}
+
+impl gl_shape::Job for Job {
+    fn tessellate(self, frame: &mut GlFrame) {
+        match self {
+            Job::Fill {
+                shape,
+                matrix,
+                color,
+            } => shape.tessellate(matrix, color, frame),
+            Job::Stroke {
+                shape,
+                style,
+                matrix,
+                color,
+            } => shape.tessellate(style, matrix, color, frame),
+            Job::Sdf {
+                shape,
+                matrix,
+                color,
+                weight,
+            } => frame.push_sdf(shape.quad(matrix, color, weight)),
+            Job::Instances(run) => run.tessellate(frame),
+            Job::Geometry {
+                geometry,
+                rule,
+                style,
+                close,
+            } => geometry.tessellate(rule, style, close, frame),
+            // a single segment and its caps never overlap
+            Job::Triangles(vertices) => frame.push(vertices, PrimitiveType::TrianglesList),
+            Job::SdfQuads(vertices) => frame.push_sdf(vertices),
+        }
+    }
+}
+
+/// Declares an enum of queued shapes, each variant holding a primitive and
+/// what else it takes to tessellate it, and its `tessellate` method pushing
+/// the variant's tessellation with `$push`. The parameters are passed to
+/// `tessellate` before the matrix, color and frame every shape takes.
+macro_rules! queued_shapes {
+    (
+        $(#[$meta:meta])*
+        enum $name:ident($($param:ident: $param_ty:ty),*) => $push:ident {
+            $($variant:ident($($field:ident: $field_ty:ty),+) => $tessellation:expr,)*
+        }
+    ) => {
+        $(#[$meta])*
+        enum $name {
+            $($variant($($field_ty),+),)*
+        }
+
+        impl $name {
+            fn tessellate(
+                self,
+                $($param: $param_ty,)*
+                matrix: Matrix2D,
+                color: Color,
+                frame: &mut GlFrame,
+            ) {
+                match self {
+                    $($name::$variant($($field),+) => $push($tessellation, matrix, color, frame),)*
+                }
+            }
+        }
+    };
+}
+
+queued_shapes! {
+    /// A filled shape, with what it takes to tessellate it
+    enum FillShape() => push_fill {
+        Triangle(triangle: Triangle) => triangle.tessellate_fill(),
+        Quad(quad: Quad) => quad.tessellate_fill(),
+        Polygon(polygon: RegularPolygon) => polygon.tessellate_fill(),
+        Star(star: Star) => star.tessellate_fill(),
+        RoundedRect(rect: RoundedRect, detail: EllipseDetail) => rect.tessellate_fill(detail),
+        Ellipse(ellipse: Ellipse, segments: usize) => ellipse.tessellate_fill(segments),
+        Arc(arc: EllipseArc, segments: usize) => arc.tessellate_fill(segments),
+        Path(path: Path, rule: FillRule, tolerance: f32) => path.tessellate_fill(rule, tolerance),
+        Curve(curve: Bezier, rule: FillRule, tolerance: f32) => {
+            curve.tessellate_fill(rule, tolerance)
+        },
+    }
+}
+
+queued_shapes! {
+    /// A stroked shape, with what it takes to tessellate it besides its style
+    enum StrokeShape(style: StrokeStyle) => push_stroke {
+        Point(point: Point) => point.tessellate_fill(style),
+        Line(line: Line) => line.tessellate_fill(style),
+        Polyline(polyline: Polyline) => polyline.tessellate_stroke(style),
+        Triangle(triangle: Triangle) => triangle.tessellate_stroke(style),
+        Quad(quad: Quad) => quad.tessellate_stroke(style),
+        Polygon(polygon: RegularPolygon) => polygon.tessellate_stroke(style),
+        Star(star: Star) => star.tessellate_stroke(style),
+        RoundedRect(rect: RoundedRect) => rect.tessellate_stroke(style),
+        Ellipse(ellipse: Ellipse, segments: usize) => ellipse.tessellate_stroke(style, segments),
+        Arc(arc: EllipseArc, segments: usize) => arc.tessellate_stroke(style, segments),
+        Path(path: Path, tolerance: f32) => path.tessellate_stroke(style, tolerance),
+        Curve(curve: Bezier, tolerance: f32) => curve.tessellate_stroke(style, tolerance),
+    }
+}
+
+/// Tessellates a fill into `frame`, transformed by `matrix`.
+fn push_fill<S, F, P>(
+    shape: Tessellate<S, F, P>,
+    matrix: Matrix2D,
+    color: Color,
+    frame: &mut GlFrame,
+) where
+    F: FnOnce(S) -> P,
+    P: IntoIterator<Item = Point>,
+{
+    let shape = shape.transform(matrix);
+    frame.push(shape.color(color), PrimitiveType::TrianglesList);
+}
+
+/// Tessellates a stroke into `frame`, transformed by `matrix`.
+fn push_stroke<S, F, P>(
+    shape: Tessellate<S, F, P>,
+    matrix: Matrix2D,
+    color: Color,
+    frame: &mut GlFrame,
+) where
+    F: FnOnce(S) -> P,
+    P: IntoIterator<Item = Point>,
+{
+    let shape = shape.transform(matrix);
+    frame.push_stroke(shape.color(color), color);
+}
+
+impl GraphicsP2D {
+    // transform
+    pub fn matrix(&self) -> Matrix2D {
+        self.matrix
+    }
+
+    pub fn apply_matrix(&mut self, matrix: Matrix2D) {
+        self.matrix = self.matrix * matrix;
+    }
+
+    pub fn reset_matrix(&mut self) {
+        self.matrix = Matrix2D::IDENTITY;
+    }
+
+    pub fn translate(&mut self, x: f32, y: f32) {
+        self.apply_matrix(Matrix2D::translation(x, y));
+    }
+
+    pub fn rotate(&mut self, angle: f32) {
+        self.apply_matrix(Matrix2D::rotation(angle));
+    }
+
+    pub fn scale(&mut self, x: f32, y: f32) {
+        self.apply_matrix(Matrix2D::scaling(x, y));
+    }
+
+    pub fn shear_x(&mut self, angle: f32) {
+        self.apply_matrix(Matrix2D::shearing_x(angle));
+    }
+
+    pub fn shear_y(&mut self, angle: f32) {
+        self.apply_matrix(Matrix2D::shearing_y(angle));
+    }
+
+    pub fn push_matrix(&mut self) {
+        self.matrix_stack.push(self.matrix);
+    }
+
+    pub fn pop_matrix(&mut self) {
+        let Some(matrix) = self.matrix_stack.pop() else {
+            panic!("pop_matrix() has been called more times than push_matrix()");
+        };
+        self.matrix = matrix;
+    }
+
+    /// Saves both the style settings and the current matrix.
+    pub fn push(&mut self) {
+        self.push_style();
+        self.push_matrix();
+    }
+
+    /// Restores what the matching `push` saved.
+    pub fn pop(&mut self) {
+        if self.style_stack.is_empty() || self.matrix_stack.is_empty() {
+            panic!("pop() has been called more times than push()");
+        }
+        self.pop_style();
+        self.pop_matrix();
+    }
+}
+
+impl GraphicsP2D {
+    // begin_shape curves
+    fn geometry(&mut self) -> &mut Geometry {
         let Some(ref mut geometry) = self.geometry else {
             panic!("begin_shape() has not been called");
         };
+        geometry
+    }
+
+    /// A vertex at `vertex` with the current style
+    fn geometry_vertex(&self, vertex: (f32, f32)) -> GeometryVertex {
         let StrokeSettings {
             fill,
             stroke,
@@ -155,28 +931,91 @@ impl BeginShape for GraphicsP2D {
             ..
         } = self.stroke_settings;
 
-        geometry.push_vertex(GeometryVertex::new(
-            vertex.0,
-            vertex.1,
-            0.0,
-            fill,
-            stroke,
-            stroke_weight,
-        ));
+        // vertices are stored transformed, so the weight is scaled by the
+        // matrix's average scale factor
+        let (x, y) = self.matrix.apply_xy(vertex.0, vertex.1);
+        let stroke_weight = stroke_weight * self.matrix.determinant().abs().sqrt();
+        GeometryVertex::new(x, y, 0.0, fill, stroke, stroke_weight)
+    }
+
+    pub fn bezier_vertex(&mut self, c1: (f32, f32), c2: (f32, f32), end: (f32, f32)) {
+        let (c1, c2) = (
+            self.matrix.apply_xy(c1.0, c1.1),
+            self.matrix.apply_xy(c2.0, c2.1),
+        );
+        let end = self.geometry_vertex(end);
+        self.geometry()
+            .bezier_vertex(Point::new(c1), Point::new(c2), end);
+    }
+
+    pub fn quadratic_vertex(&mut self, control: (f32, f32), end: (f32, f32)) {
+        let control = self.matrix.apply_xy(control.0, control.1);
+        let end = self.geometry_vertex(end);
+        self.geometry().quadratic_vertex(Point::new(control), end);
+    }
+
+    pub fn curve_vertex(&mut self, vertex: (f32, f32)) {
+        let vertex = self.geometry_vertex(vertex);
+        let tightness = self.curve_tightness;
+        self.geometry().curve_vertex(vertex, tightness);
+    }
+}
+
+impl BeginShape for GraphicsP2D {
+    type Item = (f32, f32);
+
+    fn begin_shape(&mut self, kind: GeometryKind) {
+        if self.geometry.is_some() {
+            panic!("begin_shape() has already been called");
+        }
+        self.geometry = Some(Geometry::new(kind));
+    }
+
+    fn vertex(&mut self, vertex: Self::Item) {
+        let vertex = self.geometry_vertex(vertex);
+        self.geometry().push_vertex(vertex);
     }
 
-    fn end_shape(&mut self) {
+    fn begin_contour(&mut self) {
+        self.geometry().begin_contour();
+    }
+
+    fn end_contour(&mut self) {
+        self.geometry().end_contour();
+    }
+
+    fn end_shape(&mut self, close: CloseMode) -> Result<(), GeometryError> {
         let Some(geometry) = self.geometry.take() else {
             panic!("begin_shape() has not been called");
         };
-        let lazy_shape = geometry.tessellate();
-        self.shapes.push(lazy_shape);
+        geometry.check()?;
+
+        let rule = self.stroke_settings.fill_rule;
+        let style = StrokeStyle {
+            // the vertices are already transformed
+            detail: self.detail(CURVE_TOLERANCE),
+            ..self.stroke_settings.style()
+        };
+        self.push_job(Job::Geometry {
+            geometry,
+            rule,
+            style,
+            close,
+        });
+        Ok(())
     }
 }
 
 impl Renderer for GraphicsP2D {
-    fn shapes(&mut self) -> Vec<LazyGlShape> {
-        self.shapes.drain(..).collect()
+    fn frame(&mut self) -> &mut GlFrame {
+        self.flush_run();
+        self.queue.frame()
+    }
+
+    fn begin_draw(&mut self) {
+        // like Processing, every `draw` starts from the identity transform
+        self.reset_matrix();
+        self.matrix_stack.clear();
     }
 }
 
@@ -200,13 +1039,37 @@ impl Stroke for GraphicsP2D {
     fn fill(&mut self, color: Option<Color>) {
         self.stroke_settings.fill = color;
     }
+
+    fn fill_rule(&mut self, rule: FillRule) {
+        self.stroke_settings.fill_rule = rule;
+    }
+
+    fn rect_mode(&mut self, mode: ShapeMode) {
+        self.stroke_settings.rect_mode = mode;
+    }
+
+    fn ellipse_mode(&mut self, mode: ShapeMode) {
+        self.stroke_settings.ellipse_mode = mode;
+    }
+
+    fn push_style(&mut self) {
+        self.style_stack.push(self.stroke_settings);
+    }
+
+    fn pop_style(&mut self) {
+        let Some(style) = self.style_stack.pop() else {
+            panic!("pop_style() has been called more times than push_style()");
+        };
+        self.stroke_settings = style;
+    }
 }
 
 #[derive(Default)]
 pub struct GraphicsP3D {
     stroke_settings: StrokeSettings,
+    style_stack: Vec<StrokeSettings>,
 
-    shapes: Vec<LazyGlShape>,
+    frame: GlFrame,
 }
 
 impl GraphicsP3D {
@@ -228,8 +1091,8 @@ impl GraphicsP3D {
 }
 
 impl Renderer for GraphicsP3D {
-    fn shapes(&mut self) -> Vec<LazyGlShape> {
-        self.shapes.drain(..).collect()
+    fn frame(&mut self) -> &mut GlFrame {
+        &mut self.frame
     }
 }
 
@@ -253,4 +1116,73 @@ impl Stroke for GraphicsP3D {
     fn fill(&mut self, color: Option<Color>) {
         self.stroke_settings.fill = color;
     }
+
+    fn fill_rule(&mut self, rule: FillRule) {
+        self.stroke_settings.fill_rule = rule;
+    }
+
+    fn rect_mode(&mut self, mode: ShapeMode) {
+        self.stroke_settings.rect_mode = mode;
+    }
+
+    fn ellipse_mode(&mut self, mode: ShapeMode) {
+        self.stroke_settings.ellipse_mode = mode;
+    }
+
+    fn push_style(&mut self) {
+        self.style_stack.push(self.stroke_settings);
+    }
+
+    fn pop_style(&mut self) {
+        let Some(style) = self.style_stack.pop() else {
+            panic!("pop_style() has been called more times than push_style()");
+        };
+        self.stroke_settings = style;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn close_ellipses_share_the_finer_mesh() {
+        let shared = Mesh::ellipse(16).share(Mesh::ellipse(20));
+        assert_eq!(
+            shared,
+            Some(Mesh::Ellipse {
+                segments: 20,
+                fewest: 16
+            })
+        );
+        // every circle of the run counts, not just the last one
+        assert_eq!(shared.unwrap().share(Mesh::ellipse(14)), None);
+    }
+
+    #[test]
+    fn sdf_shapes_without_area_draw_nothing() {
+        let mut g = GraphicsP2D::default();
+        g.sdf_shapes(true);
+        g.fill(Some(Color::BLACK));
+        g.stroke(None);
+        g.ellipse(32.0, 32.0, 20.0, 0.0);
+        g.rounded_rect(8.0, 8.0, 0.0, 16.0, CornerRadii::from(4.0));
+
+        g.stroke(Some(Color::BLACK));
+        g.stroke_weight(0.0);
+        g.stroke_cap(StrokeCap::Round);
+        g.point((8.0, 8.0));
+        g.line((8.0, 8.0), (24.0, 8.0));
+        g.points(&[(8.0, 8.0)], None);
+        g.lines(&[[(8.0, 8.0), (24.0, 8.0)]], None);
+
+        assert!(g.frame().shapes.is_empty());
+    }
+
+    #[test]
+    fn small_dots_do_not_share_a_large_circle() {
+        assert_eq!(Mesh::ellipse(1024).share(Mesh::ellipse(8)), None);
+        assert_eq!(Mesh::ellipse(8).share(Mesh::ellipse(1024)), None);
+        assert_eq!(Mesh::Rect.share(Mesh::ellipse(8)), None);
+    }
 }
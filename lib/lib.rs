@@ -5,10 +5,14 @@ pub use app::App;
 pub use app::Application;
 pub use color::Color;
 pub use consts::*;
-pub use geometry::GeometryKind;
+pub use geometry::{CloseMode, GeometryError, GeometryKind};
 pub use graphics::{GraphicsP2D, GraphicsP3D};
+pub use matrix::Matrix2D;
+pub use primitives::path::Path;
+pub use primitives::shapes_2d::CornerRadii;
 pub use processing::Processing;
-pub use settings::StrokeCap;
+pub use rasterizer::Rasterizer;
+pub use settings::{ArcMode, FillRule, ShapeMode, StrokeCap, StrokeJoin};
 pub use utils::*;
 pub use vector::Vector2D;
 
@@ -21,12 +25,15 @@ mod core;
 mod geometry;
 mod gl_shape;
 mod graphics;
+mod matrix;
 mod noise;
 mod painter;
 mod primitives;
 mod processing;
+mod rasterizer;
 mod settings;
 mod tess;
+pub mod testing;
 mod traits;
 mod utils;
 mod vector;
@@ -0,0 +1,126 @@
+use crate::Vector2D;
+
+/// 2D affine transform, stored row-major like Processing's `PMatrix2D`:
+///
+/// ```text
+/// | m00 m01 m02 |
+/// | m10 m11 m12 |
+/// |  0   0   1  |
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix2D {
+    pub m00: f32,
+    pub m01: f32,
+    pub m02: f32,
+    pub m10: f32,
+    pub m11: f32,
+    pub m12: f32,
+}
+
+impl Default for Matrix2D {
+    fn default() -> Self {
+        Matrix2D::IDENTITY
+    }
+}
+
+impl Matrix2D {
+    pub const IDENTITY: Matrix2D = Matrix2D::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+
+    pub const fn new(m00: f32, m01: f32, m02: f32, m10: f32, m11: f32, m12: f32) -> Matrix2D {
+        Matrix2D {
+            m00,
+            m01,
+            m02,
+            m10,
+            m11,
+            m12,
+        }
+    }
+
+    pub fn translation(x: f32, y: f32) -> Matrix2D {
+        Matrix2D::new(1.0, 0.0, x, 0.0, 1.0, y)
+    }
+
+    /// Rotation by `angle` radians
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use processing::{Matrix2D, Vector2D, HALF_PI};
+    ///
+    /// let v = Matrix2D::rotation(HALF_PI).apply(Vector2D::new(2.0, 0.0));
+    /// assert!(v.x.abs() < 1e-6);
+    /// assert_eq!(v.y, 2.0);
+    /// ```
+    pub fn rotation(angle: f32) -> Matrix2D {
+        let (sin, cos) = angle.sin_cos();
+        Matrix2D::new(cos, -sin, 0.0, sin, cos, 0.0)
+    }
+
+    pub fn scaling(x: f32, y: f32) -> Matrix2D {
+        Matrix2D::new(x, 0.0, 0.0, 0.0, y, 0.0)
+    }
+
+    pub fn shearing_x(angle: f32) -> Matrix2D {
+        Matrix2D::new(1.0, angle.tan(), 0.0, 0.0, 1.0, 0.0)
+    }
+
+    pub fn shearing_y(angle: f32) -> Matrix2D {
+        Matrix2D::new(1.0, 0.0, 0.0, angle.tan(), 1.0, 0.0)
+    }
+
+    /// Returns `self * other`: `other` is applied to a point first, then `self`.
+    pub fn multiply(&self, other: Matrix2D) -> Matrix2D {
+        Matrix2D {
+            m00: self.m00 * other.m00 + self.m01 * other.m10,
+            m01: self.m00 * other.m01 + self.m01 * other.m11,
+            m02: self.m00 * other.m02 + self.m01 * other.m12 + self.m02,
+            m10: self.m10 * other.m00 + self.m11 * other.m10,
+            m11: self.m10 * other.m01 + self.m11 * other.m11,
+            m12: self.m10 * other.m02 + self.m11 * other.m12 + self.m12,
+        }
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.m00 * self.m11 - self.m01 * self.m10
+    }
+
+    pub fn inverse(&self) -> Option<Matrix2D> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        Some(Matrix2D {
+            m00: self.m11 / det,
+            m01: -self.m01 / det,
+            m02: (self.m01 * self.m12 - self.m11 * self.m02) / det,
+            m10: -self.m10 / det,
+            m11: self.m00 / det,
+            m12: (self.m10 * self.m02 - self.m00 * self.m12) / det,
+        })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Matrix2D::IDENTITY
+    }
+
+    pub fn apply(&self, v: Vector2D) -> Vector2D {
+        let (x, y) = self.apply_xy(v.x, v.y);
+        Vector2D::new(x, y)
+    }
+
+    pub fn apply_xy(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.m00 * x + self.m01 * y + self.m02,
+            self.m10 * x + self.m11 * y + self.m12,
+        )
+    }
+}
+
+impl std::ops::Mul for Matrix2D {
+    type Output = Matrix2D;
+
+    fn mul(self, other: Matrix2D) -> Matrix2D {
+        self.multiply(other)
+    }
+}
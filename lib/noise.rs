@@ -25,24 +25,36 @@ static mut PERM: [u8; 512] = {
     perm
 };
 
+#[allow(clippy::excessive_precision)]
 const SQRT3: f64 = 1.7320508075688772935;
+#[allow(clippy::excessive_precision)]
 const SQRT5: f64 = 2.2360679774997896964;
 
+#[allow(dead_code)]
 const F2: f64 = 0.5 * (SQRT3 - 1.0);
+#[allow(dead_code)]
 const G2: f64 = (3.0 - SQRT3) / 6.0;
+#[allow(dead_code)]
 const G22: f64 = G2 * 2.0 - 1.0;
 
 const F3: f64 = 1.0 / 3.0;
 const G3: f64 = 1.0 / 6.0;
 
+#[allow(dead_code)]
 const F4: f64 = (SQRT5 - 1.0) / 4.0;
+#[allow(dead_code)]
 const G4: f64 = (5.0 - SQRT5) / 20.0;
+#[allow(dead_code)]
 const G42: f64 = G4 * 2.0;
+#[allow(dead_code)]
 const G43: f64 = G4 * 3.0;
+#[allow(dead_code)]
 const G44: f64 = G4 * 4.0 - 1.0;
 
+#[allow(dead_code)]
 const GRAD1: [f64; 2] = [1.0, -1.0];
 
+#[allow(dead_code)]
 const GRAD2: [[f64; 2]; 8] = [
     [1.0, 0.0],
     [-1.0, 0.0],
@@ -69,6 +81,7 @@ const GRAD3: [[f64; 3]; 12] = [
     [0.0, -1.0, -1.0],
 ];
 
+#[allow(dead_code)]
 pub fn seed(seed: u32) {
     let f1 = (seed & 0xff) as u8;
     let f2 = ((seed >> 8) & 0xff) as u8;
@@ -96,6 +109,7 @@ fn fast_floor(x: f64) -> i32 {
 }
 
 #[inline(always)]
+#[allow(dead_code)]
 fn dot2d(g: [f64; 2], x: f64, y: f64) -> f64 {
     g[0] * x + g[1] * y
 }
@@ -106,10 +120,12 @@ fn dot3d(g: [f64; 3], x: f64, y: f64, z: f64) -> f64 {
 }
 
 #[inline(always)]
+#[allow(dead_code)]
 fn dot4d(g: [f64; 4], x: f64, y: f64, z: f64, w: f64) -> f64 {
     g[0] * x + g[1] * y + g[2] * z + g[3] * w
 }
 
+#[allow(dead_code)]
 pub fn noise3d(x: f64, y: f64, z: f64) -> f64 {
     // noise contributions from the four corners
     // skew the input space to determine which simplex cell we're in
@@ -256,11 +272,13 @@ pub fn noise3d(x: f64, y: f64, z: f64) -> f64 {
 }
 
 #[inline(always)]
+#[allow(dead_code)]
 pub fn noise2d(x: f64, y: f64) -> f64 {
     noise3d(x, y, 0.0)
 }
 
 #[inline(always)]
+#[allow(dead_code)]
 pub fn noise1d(x: f64) -> f64 {
     noise3d(x, 0.0, 0.0)
 }
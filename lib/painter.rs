@@ -1,19 +1,480 @@
-use glium::{glutin::surface::WindowSurface, Display, Program};
-use winit::{window::Window};
+use std::ops::Range;
+
+use glium::{
+    draw_parameters::{Stencil, StencilOperation, StencilTest},
+    framebuffer::{SimpleFrameBuffer, StencilRenderBuffer},
+    glutin::surface::WindowSurface,
+    index::{NoIndices, PrimitiveType},
+    texture::{MipmapsOption, StencilFormat, UncompressedFloatFormat},
+    uniforms::MagnifySamplerFilter,
+    BlitTarget, Display, Program, Rect, Surface, Texture2d, VertexBuffer,
+};
+use image::RgbaImage;
+use winit::window::Window;
+
+use crate::{
+    core::vertex::{Instance, SdfVertex, Vertex},
+    gl_shape::{GlFrame, GlShape},
+    traits::Backend,
+};
 
-#[derive(Debug)]
 pub struct Painter {
     pub(crate) window: Window,
     pub(crate) display: Display<WindowSurface>,
     pub(crate) program: Program,
+    /// Draws the instanced shapes, placing each instance with its own transform
+    pub(crate) instanced_program: Program,
+    /// Draws the shapes from their signed distance, evaluated for each pixel
+    pub(crate) sdf_program: Program,
+
+    /// Offscreen framebuffer the sketch draws into, kept across frames
+    canvas: Option<Texture2d>,
+    /// Stencil attached to the canvas, used to draw `cover_once` shapes
+    stencil: Option<StencilRenderBuffer>,
+
+    /// GPU copy of the vertices of a frame, reused across frames and grown as needed
+    vertex_buffer: Option<VertexBuffer<Vertex>>,
+    /// Same for the instances of a frame
+    instance_buffer: Option<VertexBuffer<Instance>>,
+    /// Same for the vertices of the shapes drawn from their signed distance
+    sdf_buffer: Option<VertexBuffer<SdfVertex>>,
 }
 
 impl Painter {
-    pub fn new(window: Window, display: Display<WindowSurface>, program: Program) -> Painter {
+    pub fn new(
+        window: Window,
+        display: Display<WindowSurface>,
+        program: Program,
+        instanced_program: Program,
+        sdf_program: Program,
+    ) -> Painter {
         Painter {
             window,
             display,
             program,
+            instanced_program,
+            sdf_program,
+            canvas: None,
+            stencil: None,
+            vertex_buffer: None,
+            instance_buffer: None,
+            sdf_buffer: None,
+        }
+    }
+
+    /// (Re)creates the canvas when the sketch size changes.
+    /// The previous contents are kept, anchored at the origin.
+    fn resize_canvas(&mut self, width: u32, height: u32) -> anyhow::Result<()> {
+        let outdated = match &self.canvas {
+            Some(canvas) => canvas.dimensions() != (width, height),
+            None => true,
+        };
+
+        if outdated {
+            let canvas = Texture2d::empty_with_format(
+                &self.display,
+                UncompressedFloatFormat::U8U8U8U8,
+                MipmapsOption::NoMipmap,
+                width,
+                height,
+            )?;
+            canvas.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+
+            if let Some(old) = self.canvas.take() {
+                let (old_width, old_height) = old.dimensions();
+                let (blit_width, blit_height) = (old_width.min(width), old_height.min(height));
+                old.as_surface().blit_color(
+                    &Rect {
+                        left: 0,
+                        bottom: 0,
+                        width: blit_width,
+                        height: blit_height,
+                    },
+                    &canvas.as_surface(),
+                    &BlitTarget {
+                        left: 0,
+                        bottom: 0,
+                        width: blit_width as i32,
+                        height: blit_height as i32,
+                    },
+                    MagnifySamplerFilter::Nearest,
+                );
+            }
+
+            self.canvas = Some(canvas);
+            self.stencil = Some(StencilRenderBuffer::new(
+                &self.display,
+                StencilFormat::I8,
+                width,
+                height,
+            )?);
+        }
+
+        Ok(())
+    }
+}
+
+impl Backend for Painter {
+    fn draw(&mut self, frame: &GlFrame, width: u32, height: u32) -> anyhow::Result<()> {
+        let uniforms = uniform! {
+            projection: [
+                [2.0 / width as f32, 0.0, 0.0, 0.0],
+                [0.0, 2.0 / height as f32, 0.0, 0.0],
+                [0.0, 0.0, 2.0 / 100.0, 0.0], // TODO depth
+                [-1.0, -1.0, -1.0, 1.0]
+            ],
+        };
+
+        let params = glium::DrawParameters {
+            blend: glium::Blend {
+                color: glium::BlendingFunction::Addition {
+                    source: glium::LinearBlendingFactor::One,
+                    destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
+                },
+                alpha: glium::BlendingFunction::Addition {
+                    source: glium::LinearBlendingFactor::One,
+                    destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
+                },
+                constant_value: (0.0, 0.0, 0.0, 0.0),
+            },
+            ..Default::default()
+        };
+
+        upload(&self.display, &mut self.vertex_buffer, &frame.vertices)?;
+        upload(&self.display, &mut self.instance_buffer, &frame.instances)?;
+        upload(&self.display, &mut self.sdf_buffer, &frame.sdf_vertices)?;
+        self.resize_canvas(width, height)?;
+        let (Some(ref canvas), Some(ref stencil)) = (&self.canvas, &self.stencil) else {
+            unreachable!("canvas is created by resize_canvas");
+        };
+        let (Some(ref vertex_buffer), Some(ref instance_buffer), Some(ref sdf_buffer)) =
+            (&self.vertex_buffer, &self.instance_buffer, &self.sdf_buffer)
+        else {
+            unreachable!("vertex buffers are created by upload");
+        };
+        let mut surface = SimpleFrameBuffer::with_stencil_buffer(&self.display, canvas, stencil)?;
+        surface.clear_stencil(0);
+
+        // each cover-once shape marks the pixels it blends with its own
+        // reference value and skips the pixels already marked with it
+        let mut reference = 0;
+        for batch in batches(&frame.shapes) {
+            let params = if batch.cover_once {
+                if reference == u8::MAX as i32 {
+                    surface.clear_stencil(0);
+                    reference = 0;
+                }
+                reference += 1;
+                glium::DrawParameters {
+                    stencil: cover_once_stencil(reference),
+                    ..params.clone()
+                }
+            } else {
+                params.clone()
+            };
+
+            if batch.sdf {
+                let Some(vertices) = sdf_buffer.slice(batch.range) else {
+                    unreachable!("batches lie within the uploaded vertices");
+                };
+                surface.draw(
+                    vertices,
+                    NoIndices(batch.index_type),
+                    &self.sdf_program,
+                    &uniforms,
+                    &params,
+                )?;
+                continue;
+            }
+
+            let Some(vertices) = vertex_buffer.slice(batch.range.clone()) else {
+                unreachable!("batches lie within the uploaded vertices");
+            };
+            let Some(instances) = batch.instances else {
+                surface.draw(
+                    vertices,
+                    NoIndices(batch.index_type),
+                    &self.program,
+                    &uniforms,
+                    &params,
+                )?;
+                continue;
+            };
+
+            let Some(instance_slice) = instance_buffer.slice(instances.clone()) else {
+                unreachable!("instanced batches lie within the uploaded instances");
+            };
+            match instance_slice.per_instance() {
+                Ok(per_instance) => surface.draw(
+                    (vertices, per_instance),
+                    NoIndices(batch.index_type),
+                    &self.instanced_program,
+                    &uniforms,
+                    &params,
+                )?,
+                Err(_) => {
+                    // instancing is not supported: place every instance on the CPU instead
+                    let vertices = frame.instanced_vertices(batch.range, instances);
+                    surface.draw(
+                        &VertexBuffer::new(&self.display, &vertices)?,
+                        NoIndices(batch.index_type),
+                        &self.program,
+                        &uniforms,
+                        &params,
+                    )?;
+                }
+            }
+        }
+
+        // present the canvas; the window's back buffer is never drawn to directly
+        let target = self.display.draw();
+        surface.fill(&target, MagnifySamplerFilter::Nearest);
+        target.finish()?;
+
+        Ok(())
+    }
+
+    fn read_pixels(&self) -> anyhow::Result<RgbaImage> {
+        let Some(ref canvas) = self.canvas else {
+            anyhow::bail!("Nothing has been drawn yet");
+        };
+        let image: glium::texture::RawImage2d<'_, u8> = canvas.read();
+        let image: RgbaImage =
+            RgbaImage::from_raw(image.width, image.height, image.data.into_owned())
+                .ok_or(anyhow::anyhow!("Error reading image"))?;
+
+        Ok(image::imageops::flip_vertical(&image))
+    }
+
+    fn window(&self) -> Option<&Window> {
+        Some(&self.window)
+    }
+}
+
+/// Consecutive shapes drawn with a single call
+#[derive(Debug, PartialEq)]
+struct Batch {
+    /// Where their vertices are in the vertex buffer
+    range: Range<usize>,
+    index_type: PrimitiveType,
+    cover_once: bool,
+    /// Where their instances are in the instance buffer, if they are instanced
+    instances: Option<Range<usize>>,
+    /// Whether they are drawn from their signed distance
+    sdf: bool,
+}
+
+/// Groups the shapes into as few draw calls as their state allows.
+///
+/// Shapes made of separate primitives can be drawn together when they share
+/// the same primitive type, unless they cover once: each of those needs its
+/// own stencil reference. Strips, fans and instanced shapes are always drawn
+/// alone.
+fn batches(shapes: &[GlShape]) -> Vec<Batch> {
+    let mut batches: Vec<Batch> = Vec::new();
+    for shape in shapes {
+        let range = shape.range.clone();
+        if range.is_empty() {
+            continue;
+        }
+
+        let separate = matches!(
+            shape.index_type,
+            PrimitiveType::Points | PrimitiveType::LinesList | PrimitiveType::TrianglesList
+        );
+        match batches.last_mut() {
+            Some(last)
+                if separate
+                    && shape.instances.is_none()
+                    && last.instances.is_none()
+                    && last.sdf == shape.sdf
+                    && !shape.cover_once
+                    && !last.cover_once
+                    && last.index_type == shape.index_type
+                    && last.range.end == range.start =>
+            {
+                last.range.end = range.end;
+            }
+            _ => batches.push(Batch {
+                range,
+                index_type: shape.index_type,
+                cover_once: shape.cover_once,
+                instances: shape.instances.clone(),
+                sdf: shape.sdf,
+            }),
+        }
+    }
+    batches
+}
+
+/// Uploads `data` to `buffer`, from which each batch then draws its own
+/// range, growing it first if it is too small.
+fn upload<T: glium::Vertex>(
+    display: &Display<WindowSurface>,
+    buffer: &mut Option<VertexBuffer<T>>,
+    data: &[T],
+) -> anyhow::Result<()> {
+    let len = data.len();
+    let fits = match buffer {
+        Some(buffer) => buffer.len() >= len,
+        None => false,
+    };
+    if !fits {
+        *buffer = Some(VertexBuffer::empty_dynamic(
+            display,
+            len.next_power_of_two(),
+        )?);
+    }
+    if let Some(buffer) = buffer {
+        // the previous frame may still be drawing from the old contents
+        buffer.invalidate();
+        if let Some(slice) = buffer.slice(0..len) {
+            slice.write(data);
+        }
+    }
+
+    Ok(())
+}
+
+fn cover_once_stencil(reference: i32) -> Stencil {
+    let test = StencilTest::IfNotEqual { mask: 0xff };
+    Stencil {
+        test_clockwise: test,
+        reference_value_clockwise: reference,
+        write_mask_clockwise: 0xff,
+        depth_pass_operation_clockwise: StencilOperation::Replace,
+        test_counter_clockwise: test,
+        reference_value_counter_clockwise: reference,
+        write_mask_counter_clockwise: 0xff,
+        depth_pass_operation_counter_clockwise: StencilOperation::Replace,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(range: Range<usize>, index_type: PrimitiveType) -> GlShape {
+        GlShape {
+            range,
+            index_type,
+            cover_once: false,
+            instances: None,
+            sdf: false,
+        }
+    }
+
+    fn batch(range: Range<usize>, index_type: PrimitiveType) -> Batch {
+        Batch {
+            range,
+            index_type,
+            cover_once: false,
+            instances: None,
+            sdf: false,
         }
     }
+
+    #[test]
+    fn merges_contiguous_lists() {
+        let shapes = [
+            shape(0..3, PrimitiveType::TrianglesList),
+            shape(3..9, PrimitiveType::TrianglesList),
+            shape(9..9, PrimitiveType::LinesList),
+            shape(9..12, PrimitiveType::TrianglesList),
+            shape(12..16, PrimitiveType::LinesList),
+            shape(16..18, PrimitiveType::LinesList),
+        ];
+        assert_eq!(
+            batches(&shapes),
+            [
+                batch(0..12, PrimitiveType::TrianglesList),
+                batch(12..18, PrimitiveType::LinesList),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_strips_and_cover_once_shapes_apart() {
+        let shapes = [
+            shape(0..4, PrimitiveType::TriangleStrip),
+            shape(4..8, PrimitiveType::TriangleStrip),
+            GlShape {
+                cover_once: true,
+                ..shape(8..14, PrimitiveType::TrianglesList)
+            },
+            shape(14..17, PrimitiveType::TrianglesList),
+        ];
+        assert_eq!(
+            batches(&shapes),
+            [
+                batch(0..4, PrimitiveType::TriangleStrip),
+                batch(4..8, PrimitiveType::TriangleStrip),
+                Batch {
+                    cover_once: true,
+                    ..batch(8..14, PrimitiveType::TrianglesList)
+                },
+                batch(14..17, PrimitiveType::TrianglesList),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_instanced_shapes_apart() {
+        let instanced = |range, instances| GlShape {
+            instances: Some(instances),
+            ..shape(range, PrimitiveType::TrianglesList)
+        };
+        let shapes = [
+            shape(0..3, PrimitiveType::TrianglesList),
+            instanced(3..9, 0..100),
+            instanced(9..15, 100..164),
+            shape(15..18, PrimitiveType::TrianglesList),
+        ];
+
+        let expected_instanced = |range, instances| Batch {
+            instances: Some(instances),
+            ..batch(range, PrimitiveType::TrianglesList)
+        };
+        assert_eq!(
+            batches(&shapes),
+            [
+                batch(0..3, PrimitiveType::TrianglesList),
+                expected_instanced(3..9, 0..100),
+                expected_instanced(9..15, 100..164),
+                batch(15..18, PrimitiveType::TrianglesList),
+            ]
+        );
+    }
+
+    #[test]
+    fn merges_sdf_shapes_only_with_each_other() {
+        let sdf = |range| GlShape {
+            sdf: true,
+            ..shape(range, PrimitiveType::TrianglesList)
+        };
+        // the SDF ranges are in their own arena, so they may line up with
+        // the ranges of the other shapes
+        let shapes = [
+            shape(0..6, PrimitiveType::TrianglesList),
+            sdf(6..12),
+            sdf(12..18),
+            shape(6..12, PrimitiveType::TrianglesList),
+            sdf(18..24),
+        ];
+
+        let expected_sdf = |range| Batch {
+            sdf: true,
+            ..batch(range, PrimitiveType::TrianglesList)
+        };
+        assert_eq!(
+            batches(&shapes),
+            [
+                batch(0..6, PrimitiveType::TrianglesList),
+                expected_sdf(6..18),
+                batch(6..12, PrimitiveType::TrianglesList),
+                expected_sdf(18..24),
+            ]
+        );
+    }
 }
@@ -1,2 +1,3 @@
+pub mod path;
 pub mod shapes_2d;
 pub mod shapes_3d;
@@ -0,0 +1,233 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use crate::{
+    primitives::shapes_2d::{Bezier, Point},
+    settings::{FillRule, StrokeStyle},
+    tess::{
+        fill, primitives::GlTriangleVec, stroke, tessellate::Tessellate, tessellator::Tessellator,
+    },
+    Vector2D,
+};
+
+/// Points closer than this are considered the same point
+const EPSILON: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy)]
+enum Segment {
+    Line(Point),
+    /// Cubic Bezier curve with two control points, then the end point
+    Cubic(Point, Point, Point),
+}
+
+impl Segment {
+    fn end(&self) -> Point {
+        match *self {
+            Segment::Line(end) | Segment::Cubic(_, _, end) => end,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Subpath {
+    start: Point,
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+impl Subpath {
+    fn current(&self) -> Point {
+        self.segments.last().map_or(self.start, Segment::end)
+    }
+
+    fn polyline(&self, tolerance: f32) -> Vec<Point> {
+        let mut points = vec![self.start];
+        for segment in &self.segments {
+            match *segment {
+                Segment::Line(end) => points.push(end),
+                Segment::Cubic(c1, c2, b) => {
+                    let a = points[points.len() - 1];
+                    Bezier { a, c1, c2, b }.flatten(tolerance, &mut points);
+                }
+            }
+        }
+        points
+    }
+}
+
+/// Outline made of straight and curved segments, split into subpaths.
+///
+/// A path is built by chaining its commands, each one starting from where
+/// the previous one ended:
+///
+/// ```
+/// use processing::Path;
+///
+/// let arrow = Path::new()
+///     .move_to(0.0, 0.0)
+///     .line_to(40.0, 0.0)
+///     .quad_to(60.0, 0.0, 60.0, 20.0)
+///     .close();
+/// ```
+///
+/// Drawing commands issued before any `move_to` start from the origin.
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    subpaths: Vec<Subpath>,
+}
+
+impl Path {
+    pub fn new() -> Path {
+        Path::default()
+    }
+
+    /// Starts a new subpath at `(x, y)`.
+    pub fn move_to(mut self, x: f32, y: f32) -> Path {
+        self.subpaths.push(Subpath {
+            start: Point::new((x, y)),
+            segments: Vec::new(),
+            closed: false,
+        });
+        self
+    }
+
+    pub fn line_to(mut self, x: f32, y: f32) -> Path {
+        self.push(Segment::Line(Point::new((x, y))));
+        self
+    }
+
+    /// Quadratic Bezier curve to `(x, y)`, pulled towards `(cx, cy)`.
+    pub fn quad_to(self, cx: f32, cy: f32, x: f32, y: f32) -> Path {
+        let start = self.current();
+        let curve = Bezier::quadratic((start.x, start.y), (cx, cy), (x, y));
+        self.cubic_to(curve.c1.x, curve.c1.y, curve.c2.x, curve.c2.y, x, y)
+    }
+
+    /// Cubic Bezier curve to `(x, y)`, with control points `(c1x, c1y)` and `(c2x, c2y)`.
+    pub fn cubic_to(mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) -> Path {
+        self.push(Segment::Cubic(
+            Point::new((c1x, c1y)),
+            Point::new((c2x, c2y)),
+            Point::new((x, y)),
+        ));
+        self
+    }
+
+    /// Rounds the corner at `(x1, y1)` between the line from the current
+    /// point to it and the line from it to `(x2, y2)`, like `arcTo` in a
+    /// HTML canvas: draws a straight line to where the arc of `radius`
+    /// touches the first line, then the arc up to where it touches the second.
+    pub fn arc_to(self, x1: f32, y1: f32, x2: f32, y2: f32, radius: f32) -> Path {
+        let p0: Vector2D = self.current().into();
+        let p1 = Vector2D::new(x1, y1);
+        let p2 = Vector2D::new(x2, y2);
+
+        let (to_p0, to_p2) = (p0 - p1, p2 - p1);
+        let cross = to_p0.x * to_p2.y - to_p0.y * to_p2.x;
+        if radius <= 0.0
+            || to_p0.magnitude() < EPSILON
+            || to_p2.magnitude() < EPSILON
+            || cross.abs() < EPSILON
+        {
+            // no corner to round
+            return self.line_to(x1, y1);
+        }
+
+        let (d0, d2) = (to_p0.normalized(), to_p2.normalized());
+        let half_angle = d0.dot(d2).clamp(-1.0, 1.0).acos() / 2.0;
+        let tangent = p1 + d0 * (radius / half_angle.tan());
+        let center = p1 + (d0 + d2).normalized() * (radius / half_angle.sin());
+
+        let start_angle = (tangent - center).angle();
+        // the arc turns the same way as the corner, by less than half a turn
+        let sweep = (PI - 2.0 * half_angle) * if cross > 0.0 { -1.0 } else { 1.0 };
+        self.line_to(tangent.x, tangent.y)
+            .arc(center, radius, start_angle, sweep)
+    }
+
+    /// Connects the current subpath back to its start. The next command
+    /// starts a new subpath from that same point.
+    pub fn close(mut self) -> Path {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.closed = true;
+        }
+        self
+    }
+
+    fn current(&self) -> Point {
+        match self.subpaths.last() {
+            Some(subpath) if subpath.closed => subpath.start,
+            Some(subpath) => subpath.current(),
+            None => Point::new((0.0, 0.0)),
+        }
+    }
+
+    fn push(&mut self, segment: Segment) {
+        let start = self.current();
+        match self.subpaths.last_mut() {
+            Some(subpath) if !subpath.closed => subpath.segments.push(segment),
+            _ => self.subpaths.push(Subpath {
+                start,
+                segments: vec![segment],
+                closed: false,
+            }),
+        }
+    }
+
+    /// Circular arc around `center`, from `start` turning by `sweep` radians,
+    /// made of cubic curves spanning at most a quarter turn each.
+    fn arc(mut self, center: Vector2D, radius: f32, start: f32, sweep: f32) -> Path {
+        let pieces = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = sweep / pieces as f32;
+        // distance of the control points along the tangents
+        let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+
+        for i in 0..pieces {
+            let (a, b) = (start + i as f32 * step, start + (i + 1) as f32 * step);
+            let (from, to) = (Vector2D::from_angle(a), Vector2D::from_angle(b));
+            let c1 = center + from * radius + Vector2D::new(-from.y, from.x) * k;
+            let c2 = center + to * radius - Vector2D::new(-to.y, to.x) * k;
+            let end = center + to * radius;
+            self = self.cubic_to(c1.x, c1.y, c2.x, c2.y, end.x, end.y);
+        }
+        self
+    }
+
+    /// Each subpath flattened within `tolerance`, and whether it is closed
+    fn polylines(&self, tolerance: f32) -> Vec<(Vec<Point>, bool)> {
+        self.subpaths
+            .iter()
+            .map(|subpath| (subpath.polyline(tolerance), subpath.closed))
+            .collect()
+    }
+
+    /// Fills the area enclosed by the subpaths, each one closed implicitly.
+    pub fn tessellate_fill(
+        self,
+        rule: FillRule,
+        tolerance: f32,
+    ) -> Tessellate<Path, impl FnOnce(Path) -> Vec<Point>, Vec<Point>> {
+        self.tessellate(move |path| {
+            let contours: Vec<Vec<Point>> = path
+                .polylines(tolerance)
+                .into_iter()
+                .map(|(points, _)| points)
+                .collect();
+            fill::polygon(&contours, rule)
+        })
+    }
+
+    pub fn tessellate_stroke(
+        self,
+        style: StrokeStyle,
+        tolerance: f32,
+    ) -> Tessellate<Path, impl FnOnce(Path) -> GlTriangleVec, GlTriangleVec> {
+        self.tessellate(move |path| {
+            path.polylines(tolerance)
+                .into_iter()
+                .map(|(points, closed)| stroke::polyline(&points, closed, style))
+                .fold(GlTriangleVec::new(), GlTriangleVec::and)
+        })
+    }
+}
+
+impl Tessellator for Path {}
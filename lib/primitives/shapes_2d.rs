@@ -1,13 +1,28 @@
+use std::{
+    f32::consts::{FRAC_PI_2, TAU},
+    iter::Flatten,
+};
+
 use crate::{
+    settings::{ArcMode, EllipseDetail, FillRule, StrokeStyle},
     tess::{
+        fill::{self, FillVertex},
         fns::gl_triangle,
-        primitives::{GlTriangle, GlTriangleVec},
+        primitives::{EllipseFan, GlTriangle, GlTriangleVec},
+        stroke,
         tessellate::Tessellate,
         tessellator::Tessellator,
     },
-    StrokeCap,
+    utils::{curve_to_bezier, lerp},
+    Vector2D,
 };
 
+/// Maximum distance, in pixels, between a curve and the polyline drawn for it
+pub(crate) const CURVE_TOLERANCE: f32 = 0.25;
+
+/// Deepest subdivision of a curve, at most 2^16 segments
+const MAX_CURVE_DEPTH: u32 = 16;
+
 /// Point
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point {
@@ -23,17 +38,42 @@ impl Point {
         }
     }
 
+    pub(crate) fn approx_eq(self, other: Point) -> bool {
+        (self.x - other.x).abs() < 1e-3 && (self.y - other.y).abs() < 1e-3
+    }
+
     pub fn tessellate_fill(
         self,
-        weight: f32,
-        cap: StrokeCap,
-    ) -> Tessellate<Point, impl FnOnce(Point) -> GlTriangleVec, GlTriangleVec> {
-        self.tessellate(move |point| gl_triangle::point(point, weight, cap))
+        style: StrokeStyle,
+    ) -> Tessellate<Point, impl FnOnce(Point) -> Flatten<EllipseFan>, Flatten<EllipseFan>> {
+        self.tessellate(move |point| gl_triangle::point(point, style).flatten())
     }
 }
 
 impl Tessellator for Point {}
 
+impl From<Vector2D> for Point {
+    fn from(v: Vector2D) -> Point {
+        Point { x: v.x, y: v.y }
+    }
+}
+
+impl FillVertex for Point {
+    fn xy(&self) -> (f32, f32) {
+        (self.x, self.y)
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        lerp(Vector2D::from(*self), Vector2D::from(*other), t).into()
+    }
+}
+
+impl From<Point> for Vector2D {
+    fn from(p: Point) -> Vector2D {
+        Vector2D::new(p.x, p.y)
+    }
+}
+
 /// Edge
 pub struct Edge {
     pub a: Point,
@@ -53,15 +93,38 @@ impl Edges {
         Edges(edges)
     }
 
+    #[allow(dead_code)]
     pub fn intersections(&self) -> Vec<(&Edge, &Edge)> {
         self.0.iter().zip(self.0.iter().cycle().skip(1)).collect()
     }
 
+    /// Chains connected edges into polylines, flagging the ones that end
+    /// where they started as closed.
+    pub fn polylines(self) -> Vec<(Vec<Point>, bool)> {
+        let mut polylines: Vec<(Vec<Point>, bool)> = Vec::new();
+        for edge in self.0 {
+            match polylines.last_mut() {
+                Some((points, _)) if points.last().is_some_and(|&p| p.approx_eq(edge.a)) => {
+                    points.push(edge.b)
+                }
+                _ => polylines.push((vec![edge.a, edge.b], false)),
+            }
+        }
+
+        for (points, closed) in &mut polylines {
+            if points.len() > 3 && points[0].approx_eq(points[points.len() - 1]) {
+                points.pop();
+                *closed = true;
+            }
+        }
+        polylines
+    }
+
     pub fn tessellate_stroke(
         self,
-        weight: f32,
+        style: StrokeStyle,
     ) -> Tessellate<Edges, impl FnOnce(Edges) -> GlTriangleVec, GlTriangleVec> {
-        self.tessellate(move |edges| gl_triangle::stroke(edges, weight))
+        self.tessellate(move |edges| gl_triangle::stroke(edges, style))
     }
 }
 
@@ -92,15 +155,36 @@ impl Line {
 
     pub fn tessellate_fill(
         self,
-        weight: f32,
-        cap: StrokeCap,
+        style: StrokeStyle,
     ) -> Tessellate<Line, impl FnOnce(Line) -> GlTriangleVec, GlTriangleVec> {
-        self.tessellate(move |line| gl_triangle::line(line.a, line.b, weight, cap))
+        self.tessellate(move |line| gl_triangle::line(line.a, line.b, style))
     }
 }
 
 impl Tessellator for Line {}
 
+/// Polyline, open at both ends
+pub struct Polyline {
+    pub points: Vec<Point>,
+}
+
+impl Polyline {
+    pub fn new(points: &[(f32, f32)]) -> Polyline {
+        Polyline {
+            points: points.iter().copied().map(Point::new).collect(),
+        }
+    }
+
+    pub fn tessellate_stroke(
+        self,
+        style: StrokeStyle,
+    ) -> Tessellate<Polyline, impl FnOnce(Polyline) -> GlTriangleVec, GlTriangleVec> {
+        self.tessellate(move |polyline| stroke::polyline(&polyline.points, false, style))
+    }
+}
+
+impl Tessellator for Polyline {}
+
 /// Triangle
 pub struct Triangle {
     pub a: Point,
@@ -133,9 +217,9 @@ impl Triangle {
 
     pub fn tessellate_stroke(
         self,
-        weight: f32,
+        style: StrokeStyle,
     ) -> Tessellate<Edges, impl FnOnce(Edges) -> GlTriangleVec, GlTriangleVec> {
-        self.edges().tessellate_stroke(weight)
+        self.edges().tessellate_stroke(style)
     }
 }
 
@@ -180,14 +264,260 @@ impl Quad {
 
     pub fn tessellate_stroke(
         self,
-        weight: f32,
+        style: StrokeStyle,
     ) -> Tessellate<Edges, impl FnOnce(Edges) -> GlTriangleVec, GlTriangleVec> {
-        self.edges().tessellate_stroke(weight)
+        self.edges().tessellate_stroke(style)
     }
 }
 
 impl Tessellator for Quad {}
 
+/// Radii of the corners of a rounded rectangle, as seen on screen
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CornerRadii {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
+impl CornerRadii {
+    pub fn new(top_left: f32, top_right: f32, bottom_right: f32, bottom_left: f32) -> CornerRadii {
+        CornerRadii {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+}
+
+/// The same radius for every corner
+impl From<f32> for CornerRadii {
+    fn from(radius: f32) -> CornerRadii {
+        CornerRadii::new(radius, radius, radius, radius)
+    }
+}
+
+/// Top left, top right, bottom right and bottom left radii
+impl From<[f32; 4]> for CornerRadii {
+    fn from([tl, tr, br, bl]: [f32; 4]) -> CornerRadii {
+        CornerRadii::new(tl, tr, br, bl)
+    }
+}
+
+/// Top left, top right, bottom right and bottom left radii
+impl From<(f32, f32, f32, f32)> for CornerRadii {
+    fn from((tl, tr, br, bl): (f32, f32, f32, f32)) -> CornerRadii {
+        CornerRadii::new(tl, tr, br, bl)
+    }
+}
+
+/// RoundedRect
+pub struct RoundedRect {
+    /// Bottom left corner
+    pub min: Point,
+    /// Top right corner
+    pub max: Point,
+    pub radii: CornerRadii,
+}
+
+impl RoundedRect {
+    /// Like `Quad::rect`, with each corner rounded by a quarter circle. The
+    /// radii are clamped between 0 and half the shorter side.
+    pub fn new(x: f32, y: f32, w: f32, h: f32, radii: CornerRadii) -> RoundedRect {
+        let limit = w.abs().min(h.abs()) / 2.0;
+        let clamp = |radius: f32| radius.clamp(0.0, limit);
+        RoundedRect {
+            min: Point::new((x.min(x + w), y.min(y + h))),
+            max: Point::new((x.max(x + w), y.max(y + h))),
+            radii: CornerRadii::new(
+                clamp(radii.top_left),
+                clamp(radii.top_right),
+                clamp(radii.bottom_right),
+                clamp(radii.bottom_left),
+            ),
+        }
+    }
+
+    /// The outline, counter-clockwise from the bottom of the right side
+    fn outline(&self, detail: EllipseDetail) -> Vec<Point> {
+        let (min, max, radii) = (self.min, self.max, self.radii);
+        let corners = [
+            (max.x, min.y, radii.bottom_right, -1.0, 1.0),
+            (max.x, max.y, radii.top_right, -1.0, -1.0),
+            (min.x, max.y, radii.top_left, 1.0, -1.0),
+            (min.x, min.y, radii.bottom_left, 1.0, 1.0),
+        ];
+
+        let mut points = Vec::new();
+        for (i, (x, y, radius, dx, dy)) in corners.into_iter().enumerate() {
+            if radius == 0.0 {
+                points.push(Point::new((x, y)));
+                continue;
+            }
+            // quarter turn around the center of the corner, starting
+            // straight below it for the bottom right corner
+            let center = Point::new((x + dx * radius, y + dy * radius));
+            let start = (i as f32 - 1.0) * FRAC_PI_2;
+            let segments = detail.arc_segments(radius, FRAC_PI_2);
+            points.extend(gl_triangle::arc_points(
+                center,
+                (radius, radius),
+                start,
+                FRAC_PI_2,
+                segments,
+            ));
+        }
+        points
+    }
+
+    pub fn tessellate_fill(
+        self,
+        detail: EllipseDetail,
+    ) -> Tessellate<RoundedRect, impl FnOnce(RoundedRect) -> GlTriangleVec, GlTriangleVec> {
+        self.tessellate(move |rect| {
+            // the outline is convex, so a fan around any point of it covers it
+            let points = rect.outline(detail);
+            GlTriangleVec::from_elem(
+                points[1..]
+                    .windows(2)
+                    .map(|edge| GlTriangle::new(points[0], edge[0], edge[1]))
+                    .collect(),
+            )
+        })
+    }
+
+    pub fn tessellate_stroke(
+        self,
+        style: StrokeStyle,
+    ) -> Tessellate<RoundedRect, impl FnOnce(RoundedRect) -> GlTriangleVec, GlTriangleVec> {
+        self.tessellate(move |rect| stroke::polyline(&rect.outline(style.detail), true, style))
+    }
+}
+
+impl Tessellator for RoundedRect {}
+
+/// Triangles from `center` to each side of the closed outline `points`,
+/// which covers the shapes every point of whose outline can be seen from it.
+fn center_fan(center: Point, points: &[Point]) -> GlTriangleVec {
+    let next = points.iter().cycle().skip(1);
+    GlTriangleVec::from_elem(
+        points
+            .iter()
+            .zip(next)
+            .map(|(&a, &b)| GlTriangle::new(center, a, b))
+            .collect(),
+    )
+}
+
+/// Points at `radii` in turn around `center`, evenly spaced, starting straight
+/// above it turned counter-clockwise by `rotation`.
+fn star_points(center: Point, radii: &[f32], count: usize, rotation: f32) -> Vec<Point> {
+    let step = TAU / count as f32;
+    (0..count)
+        .map(|i| {
+            let angle = FRAC_PI_2 + rotation + i as f32 * step;
+            let radius = radii[i % radii.len()];
+            Point::new((
+                center.x + angle.cos() * radius,
+                center.y + angle.sin() * radius,
+            ))
+        })
+        .collect()
+}
+
+/// RegularPolygon
+pub struct RegularPolygon {
+    pub center: Point,
+    /// Distance from the center to the vertices
+    pub radius: f32,
+    pub sides: usize,
+    pub rotation: f32,
+}
+
+impl RegularPolygon {
+    /// Polygon with a vertex straight above `center`, turned counter-clockwise
+    /// by `rotation` radians. It is empty with fewer than 3 sides.
+    pub fn new(center: (f32, f32), radius: f32, sides: usize, rotation: f32) -> RegularPolygon {
+        RegularPolygon {
+            center: Point::new(center),
+            radius,
+            sides,
+            rotation,
+        }
+    }
+
+    fn points(&self) -> Vec<Point> {
+        if self.sides < 3 {
+            return Vec::new();
+        }
+        star_points(self.center, &[self.radius], self.sides, self.rotation)
+    }
+
+    pub fn tessellate_fill(
+        self,
+    ) -> Tessellate<RegularPolygon, impl FnOnce(RegularPolygon) -> GlTriangleVec, GlTriangleVec>
+    {
+        self.tessellate(move |polygon| center_fan(polygon.center, &polygon.points()))
+    }
+
+    pub fn tessellate_stroke(
+        self,
+        style: StrokeStyle,
+    ) -> Tessellate<RegularPolygon, impl FnOnce(RegularPolygon) -> GlTriangleVec, GlTriangleVec>
+    {
+        self.tessellate(move |polygon| stroke::polyline(&polygon.points(), true, style))
+    }
+}
+
+impl Tessellator for RegularPolygon {}
+
+/// Star
+pub struct Star {
+    pub center: Point,
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub points: usize,
+}
+
+impl Star {
+    /// Star with `points` tips at `outer_radius` from `center`, one of them
+    /// straight above it, and its inner corners at `inner_radius`. It is
+    /// empty with fewer than 2 tips.
+    pub fn new(center: (f32, f32), inner_radius: f32, outer_radius: f32, points: usize) -> Star {
+        Star {
+            center: Point::new(center),
+            inner_radius,
+            outer_radius,
+            points,
+        }
+    }
+
+    fn outline(&self) -> Vec<Point> {
+        if self.points < 2 {
+            return Vec::new();
+        }
+        let radii = [self.outer_radius, self.inner_radius];
+        star_points(self.center, &radii, 2 * self.points, 0.0)
+    }
+
+    pub fn tessellate_fill(
+        self,
+    ) -> Tessellate<Star, impl FnOnce(Star) -> GlTriangleVec, GlTriangleVec> {
+        self.tessellate(move |star| center_fan(star.center, &star.outline()))
+    }
+
+    pub fn tessellate_stroke(
+        self,
+        style: StrokeStyle,
+    ) -> Tessellate<Star, impl FnOnce(Star) -> GlTriangleVec, GlTriangleVec> {
+        self.tessellate(move |star| stroke::polyline(&star.outline(), true, style))
+    }
+}
+
+impl Tessellator for Star {}
+
 /// Ellipse
 pub struct Ellipse {
     pub center: Point,
@@ -226,16 +556,18 @@ impl Ellipse {
     pub fn tessellate_fill(
         self,
         segments: usize,
-    ) -> Tessellate<Ellipse, impl FnOnce(Ellipse) -> GlTriangleVec, GlTriangleVec> {
-        self.tessellate(move |ellipse| gl_triangle::ellipse(ellipse.center, ellipse.axes, segments))
+    ) -> Tessellate<Ellipse, impl FnOnce(Ellipse) -> Flatten<EllipseFan>, Flatten<EllipseFan>> {
+        self.tessellate(move |ellipse| {
+            gl_triangle::ellipse(ellipse.center, ellipse.axes, segments).flatten()
+        })
     }
 
     pub fn tessellate_stroke(
         self,
-        weight: f32,
+        style: StrokeStyle,
         segments: usize,
     ) -> Tessellate<Edges, impl FnOnce(Edges) -> GlTriangleVec, GlTriangleVec> {
-        self.edges(segments).tessellate_stroke(weight)
+        self.edges(segments).tessellate_stroke(style)
     }
 }
 
@@ -245,49 +577,59 @@ impl Tessellator for Ellipse {}
 pub struct EllipseArc {
     pub center: Point,
     pub axes: (f32, f32),
+    /// Between 0 and TAU
     pub start_angle: f32,
-    pub end_angle: f32,
+    /// Angle covered counter-clockwise from `start_angle`, at most TAU
+    pub sweep: f32,
+    pub mode: ArcMode,
 }
 
 impl EllipseArc {
+    /// The arc between the `start` and `stop` angles, given in either order
+    /// and in any turn. It covers at most a full turn.
     pub fn new(
         center: (f32, f32),
         axes: (f32, f32),
-        start_angle: f32,
-        end_angle: f32,
+        start: f32,
+        stop: f32,
+        mode: ArcMode,
     ) -> EllipseArc {
+        let (start, stop) = if stop < start {
+            (stop, start)
+        } else {
+            (start, stop)
+        };
         EllipseArc {
             center: Point::new(center),
             axes,
-            start_angle,
-            end_angle,
+            start_angle: start.rem_euclid(TAU),
+            sweep: (stop - start).min(TAU),
+            mode,
         }
     }
 
     fn edges(self, segments: usize) -> Edges {
-        let step = (self.end_angle - self.start_angle) / segments as f32;
-        let points = (0..segments)
-            .map(|i| {
-                (
-                    self.start_angle + i as f32 * step,
-                    self.start_angle + (i + 1) as f32 * step,
-                )
-            })
-            .map(|(a, b)| {
-                (
-                    Point::new((
-                        self.center.x + a.cos() * self.axes.0,
-                        self.center.y + a.sin() * self.axes.1,
-                    )),
-                    Point::new((
-                        self.center.x + b.cos() * self.axes.0,
-                        self.center.y + b.sin() * self.axes.1,
-                    )),
-                )
-            })
-            .map(|(a, b)| Edge::new(a, b))
-            .collect();
-        Edges::new(points)
+        let mut points = gl_triangle::arc_points(
+            self.center,
+            self.axes,
+            self.start_angle,
+            self.sweep,
+            segments,
+        );
+        match self.mode {
+            ArcMode::Open => {}
+            ArcMode::Chord => points.push(points[0]),
+            ArcMode::Pie => {
+                points.insert(0, self.center);
+                points.push(self.center);
+            }
+        }
+        Edges::new(
+            points
+                .windows(2)
+                .map(|edge| Edge::new(edge[0], edge[1]))
+                .collect(),
+        )
     }
 
     pub fn tessellate_fill(
@@ -299,19 +641,165 @@ impl EllipseArc {
                 arc.center,
                 arc.axes,
                 arc.start_angle,
-                arc.end_angle,
+                arc.sweep,
                 segments,
+                arc.mode,
             )
         })
     }
 
     pub fn tessellate_stroke(
         self,
-        weight: f32,
+        style: StrokeStyle,
         segments: usize,
     ) -> Tessellate<Edges, impl FnOnce(Edges) -> GlTriangleVec, GlTriangleVec> {
-        self.edges(segments).tessellate_stroke(weight)
+        self.edges(segments).tessellate_stroke(style)
     }
 }
 
 impl Tessellator for EllipseArc {}
+
+/// Cubic Bezier curve from `a` to `b`, pulled towards the control points `c1` and `c2`
+#[derive(Debug, Clone, Copy)]
+pub struct Bezier {
+    pub a: Point,
+    pub c1: Point,
+    pub c2: Point,
+    pub b: Point,
+}
+
+impl Bezier {
+    pub fn new(a: (f32, f32), c1: (f32, f32), c2: (f32, f32), b: (f32, f32)) -> Bezier {
+        Bezier {
+            a: Point::new(a),
+            c1: Point::new(c1),
+            c2: Point::new(c2),
+            b: Point::new(b),
+        }
+    }
+
+    /// The cubic curve drawing the same shape as a quadratic one
+    pub fn quadratic(a: (f32, f32), control: (f32, f32), b: (f32, f32)) -> Bezier {
+        let (a, control, b) = (Point::new(a), Point::new(control), Point::new(b));
+        Bezier {
+            a,
+            c1: a.lerp(&control, 2.0 / 3.0),
+            c2: b.lerp(&control, 2.0 / 3.0),
+            b,
+        }
+    }
+
+    /// Catmull-Rom segment from `b` to `c`, shaped by the neighbouring points
+    /// `a` and `d`. See `curve_to_bezier` for `tightness`.
+    pub fn curve(
+        a: (f32, f32),
+        b: (f32, f32),
+        c: (f32, f32),
+        d: (f32, f32),
+        tightness: f32,
+    ) -> Bezier {
+        let [a, c1, c2, b] = curve_to_bezier(
+            Vector2D::new(a.0, a.1),
+            Vector2D::new(b.0, b.1),
+            Vector2D::new(c.0, c.1),
+            Vector2D::new(d.0, d.1),
+            tightness,
+        );
+        Bezier {
+            a: a.into(),
+            c1: c1.into(),
+            c2: c2.into(),
+            b: b.into(),
+        }
+    }
+
+    fn control_points(&self) -> [Vector2D; 4] {
+        [self.a, self.c1, self.c2, self.b].map(Vector2D::from)
+    }
+
+    /// Splits the curve at `t` = 0.5 (de Casteljau)
+    fn split(&self) -> (Bezier, Bezier) {
+        let ab = self.a.lerp(&self.c1, 0.5);
+        let bc = self.c1.lerp(&self.c2, 0.5);
+        let cd = self.c2.lerp(&self.b, 0.5);
+        let abc = ab.lerp(&bc, 0.5);
+        let bcd = bc.lerp(&cd, 0.5);
+        let middle = abc.lerp(&bcd, 0.5);
+        (
+            Bezier {
+                a: self.a,
+                c1: ab,
+                c2: abc,
+                b: middle,
+            },
+            Bezier {
+                a: middle,
+                c1: bcd,
+                c2: cd,
+                b: self.b,
+            },
+        )
+    }
+
+    /// Whether the chord from `a` to `b` is within `tolerance` of the curve
+    fn is_flat(&self, tolerance: f32) -> bool {
+        let [a, c1, c2, b] = self.control_points();
+        let u = c1 * 3.0 - a * 2.0 - b;
+        let v = c2 * 3.0 - a - b * 2.0;
+        let x = (u.x * u.x).max(v.x * v.x);
+        let y = (u.y * u.y).max(v.y * v.y);
+        x + y <= 16.0 * tolerance * tolerance
+    }
+
+    /// Appends the points of a polyline approximating the curve within
+    /// `tolerance`, leaving out the start point `a`. Flat parts of the
+    /// curve get fewer points than tight bends.
+    pub fn flatten(&self, tolerance: f32, points: &mut Vec<Point>) {
+        self.flatten_to_depth(tolerance, MAX_CURVE_DEPTH, points);
+    }
+
+    fn flatten_to_depth(&self, tolerance: f32, depth: u32, points: &mut Vec<Point>) {
+        if depth == 0 || self.is_flat(tolerance) {
+            points.push(self.b);
+            return;
+        }
+        let (first, second) = self.split();
+        first.flatten_to_depth(tolerance, depth - 1, points);
+        second.flatten_to_depth(tolerance, depth - 1, points);
+    }
+
+    fn polyline(&self, tolerance: f32) -> Vec<Point> {
+        let mut points = vec![self.a];
+        self.flatten(tolerance, &mut points);
+        points
+    }
+
+    fn edges(self, tolerance: f32) -> Edges {
+        let points = self.polyline(tolerance);
+        Edges::new(
+            points
+                .windows(2)
+                .map(|edge| Edge::new(edge[0], edge[1]))
+                .collect(),
+        )
+    }
+
+    /// Fills the area between the curve and the chord from `a` to `b`
+    pub fn tessellate_fill(
+        self,
+        rule: FillRule,
+        tolerance: f32,
+    ) -> Tessellate<Bezier, impl FnOnce(Bezier) -> Vec<Point>, Vec<Point>> {
+        self.tessellate(move |bezier| fill::polygon(&[bezier.polyline(tolerance)], rule))
+    }
+
+    pub fn tessellate_stroke(
+        self,
+        style: StrokeStyle,
+        tolerance: f32,
+    ) -> Tessellate<Edges, impl FnOnce(Edges) -> GlTriangleVec, GlTriangleVec> {
+        self.edges(tolerance).tessellate_stroke(style)
+    }
+}
+
+impl Tessellator for Bezier {}
@@ -1,22 +1,18 @@
+use std::time::{Duration, Instant};
 
-
-use glium::{
-    index::NoIndices, Surface,
-};
-use image::Rgba;
+use image::RgbaImage;
 use winit::{
     event::{ElementState, Event, MouseButton, WindowEvent},
-    event_loop::{EventLoop, EventLoopWindowTarget},
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
     keyboard::{KeyCode, PhysicalKey},
 };
 
 use crate::{
-    geometry::GeometryKind,
+    geometry::{CloseMode, GeometryError, GeometryKind},
     graphics::{GraphicsP2D, GraphicsP3D},
-    painter::Painter,
-    settings::{StrokeCap, StrokeJoin, WindowSettings},
-    traits::{BeginShape, Renderer, Stroke},
-    Color,
+    settings::{ArcMode, FillRule, ShapeMode, StrokeCap, StrokeJoin, WindowSettings},
+    traits::{Backend, BeginShape, Renderer, Stroke},
+    utils, Color, CornerRadii, Matrix2D, Path,
 };
 
 pub type SetupFn<S, R> = Box<dyn Fn(&mut Processing<S, R>)>;
@@ -31,23 +27,30 @@ pub struct Processing<S, R: Renderer> {
 
     window_settings: WindowSettings,
     is_loop: bool,
+    redraw_pending: bool,
 
-    frame_rate: u32,
+    frame_rate: f32,
     frame_count: u32,
+    measured_frame_rate: f32,
+
+    start_time: Instant,
+    last_frame: Option<Instant>,
+    next_frame: Instant,
+    delta_time: Duration,
 
     draw: Option<DrawFn<S, R>>,
     mouse_clicked: Option<MouseClickedFn<S, R>>,
     mouse_moved: Option<MouseMovedFn<S, R>>,
     key_pressed: Option<KeyPressedFn<S, R>>,
 
-    painter: Painter,
+    backend: Box<dyn Backend>,
 }
 
 impl<S, R: Renderer + Default> Processing<S, R> {
     pub(crate) fn new(
         state: S,
         window_settings: WindowSettings,
-        painter: Painter,
+        backend: Box<dyn Backend>,
         draw: Option<DrawFn<S, R>>,
         mouse_clicked: Option<MouseClickedFn<S, R>>,
         mouse_moved: Option<MouseMovedFn<S, R>>,
@@ -58,13 +61,20 @@ impl<S, R: Renderer + Default> Processing<S, R> {
             g: R::default(),
             window_settings,
             is_loop: true,
-            frame_rate: 1,
+            // like Processing, `draw` runs at least once even after `no_loop` in `setup`
+            redraw_pending: true,
+            frame_rate: 60.0,
             frame_count: 0,
+            measured_frame_rate: 60.0,
+            start_time: Instant::now(),
+            last_frame: None,
+            next_frame: Instant::now(),
+            delta_time: Duration::ZERO,
             draw,
             mouse_clicked,
             mouse_moved,
             key_pressed,
-            painter,
+            backend,
         }
     }
 }
@@ -78,7 +88,9 @@ impl<S, R: Renderer> Processing<S, R> {
     //
     pub fn title(&mut self, title: &str) {
         self.window_settings.title = title.to_string();
-        self.painter.window.set_title(title);
+        if let Some(window) = self.backend.window() {
+            window.set_title(title);
+        }
     }
 
     pub fn width(&self) -> u32 {
@@ -112,6 +124,22 @@ impl<S, R: Renderer + Stroke> Processing<S, R> {
         self.g.fill(Some(color));
     }
 
+    /// Sets how polygons with holes or crossing edges are filled.
+    pub fn fill_rule(&mut self, rule: FillRule) {
+        self.g.fill_rule(rule);
+    }
+
+    /// Sets how `rect` and `square` interpret their arguments. Defaults to `ShapeMode::Corner`.
+    pub fn rect_mode(&mut self, mode: ShapeMode) {
+        self.g.rect_mode(mode);
+    }
+
+    /// Sets how `ellipse`, `circle` and `arc` interpret their arguments.
+    /// Defaults to `ShapeMode::Center`.
+    pub fn ellipse_mode(&mut self, mode: ShapeMode) {
+        self.g.ellipse_mode(mode);
+    }
+
     pub fn no_stroke(&mut self) {
         self.g.stroke(None);
     }
@@ -119,102 +147,119 @@ impl<S, R: Renderer + Stroke> Processing<S, R> {
     pub fn no_fill(&mut self) {
         self.g.fill(None);
     }
+
+    /// Saves the fill, fill rule, stroke, weight, cap, join and shape mode settings.
+    pub fn push_style(&mut self) {
+        self.g.push_style();
+    }
+
+    /// Restores the settings saved by the matching `push_style`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no matching `push_style`.
+    pub fn pop_style(&mut self) {
+        self.g.pop_style();
+    }
 }
 
 impl<S, R: Renderer> Processing<S, R> {
     // structure
     pub fn r#loop(&mut self) {
         self.is_loop = true;
+        if let Some(window) = self.backend.window() {
+            window.request_redraw();
+        }
     }
 
     pub fn no_loop(&mut self) {
         self.is_loop = false;
     }
 
-    pub fn redraw(&mut self) {
-        self.painter.window.request_redraw();
+    pub fn is_looping(&self) -> bool {
+        self.is_loop
     }
 
-    pub fn screenshot(&self, path: &str) -> anyhow::Result<()> {
-        let image = self
-            .painter
-            .display
-            .read_front_buffer::<glium::texture::RawImage2d<'_, u8>>()?;
-        let image: image::ImageBuffer<Rgba<u8>, Vec<u8>> =
-            image::ImageBuffer::from_raw(image.width, image.height, image.data.into_owned())
-                .ok_or(anyhow::anyhow!("Error reading image"))?;
-        let image = image::DynamicImage::ImageRgba8(image).flipv();
-        image.save(path)?;
+    /// Runs `draw` once more, even when the sketch is not looping.
+    pub fn redraw(&mut self) {
+        self.redraw_pending = true;
+        if let Some(window) = self.backend.window() {
+            window.request_redraw();
+        }
+    }
 
-        Ok(())
+    // time
+    /// Sets the target number of frames drawn per second.
+    pub fn frame_rate(&mut self, fps: f32) {
+        if fps > 0.0 {
+            self.frame_rate = fps;
+        }
     }
 
-    fn draw_shapes(&mut self, target: &mut glium::Frame) -> anyhow::Result<()> {
-        let uniforms = uniform! {
-            projection: [
-                [2.0 / self.width() as f32, 0.0, 0.0, 0.0],
-                [0.0, 2.0 / self.height() as f32, 0.0, 0.0],
-                [0.0, 0.0, 2.0 / 100.0, 0.0], // TODO depth
-                [-1.0, -1.0, -1.0, 1.0]
-            ],
-        };
+    /// Number of frames drawn since the sketch started; 1 during the first `draw`.
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
 
-        let params = glium::DrawParameters {
-            blend: glium::Blend {
-                color: glium::BlendingFunction::Addition {
-                    source: glium::LinearBlendingFactor::One,
-                    destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
-                },
-                alpha: glium::BlendingFunction::Addition {
-                    source: glium::LinearBlendingFactor::One,
-                    destination: glium::LinearBlendingFactor::OneMinusSourceAlpha,
-                },
-                constant_value: (0.0, 0.0, 0.0, 0.0),
-            },
-            ..Default::default()
-        };
+    /// Measured frames per second, smoothed over the last frames.
+    pub fn fps(&self) -> f32 {
+        self.measured_frame_rate
+    }
 
-        let lazy_gl_shapes = self.g.shapes();
-        let gl_shapes = lazy_gl_shapes
-            .into_iter()
-            .map(|shape| shape.run())
-            .collect::<Vec<_>>();
-
-        for gl_shape in &gl_shapes {
-            let vertex_buffer =
-                glium::VertexBuffer::new(&self.painter.display, &gl_shape.vertices)?;
-
-            target.draw(
-                &vertex_buffer,
-                NoIndices(gl_shape.index_type),
-                &self.painter.program,
-                &uniforms,
-                &params,
-            )?;
-        }
+    /// Milliseconds elapsed since the sketch started.
+    pub fn millis(&self) -> u64 {
+        self.start_time.elapsed().as_millis() as u64
+    }
 
-        Ok(())
+    /// Seconds elapsed between the start of the previous frame and the current one.
+    pub fn delta_time(&self) -> f32 {
+        self.delta_time.as_secs_f32()
     }
 
-    fn draw_frame(&mut self) -> anyhow::Result<()> {
-        let mut target = self.painter.display.draw();
+    fn frame_time(&self) -> Duration {
+        Duration::from_secs_f32(1.0 / self.frame_rate)
+    }
 
-        self.draw_shapes(&mut target)?;
+    fn tick(&mut self) {
+        let now = Instant::now();
+        if let Some(last_frame) = self.last_frame {
+            self.delta_time = now - last_frame;
+            let rate = 1.0 / self.delta_time.as_secs_f32().max(f32::EPSILON);
+            // same smoothing Processing applies to `frameRate`
+            self.measured_frame_rate = self.measured_frame_rate * 0.9 + rate * 0.1;
+        }
+        self.last_frame = Some(now);
+        self.next_frame = now + self.frame_time();
+        self.frame_count += 1;
+    }
 
-        target.finish()?;
+    pub fn screenshot(&self, path: &str) -> anyhow::Result<()> {
+        self.backend.read_pixels()?.save(path)?;
 
         Ok(())
     }
 
+    fn draw_frame(&mut self) -> anyhow::Result<()> {
+        let (width, height) = (self.width(), self.height());
+        let frame = self.g.frame();
+        let result = self.backend.draw(frame, width, height);
+        // even when drawing fails, its shapes must not be drawn again next frame
+        frame.clear();
+        result
+    }
+
     fn handle_draw(&mut self) -> anyhow::Result<()> {
-        if !self.is_loop {
-            return Ok(());
-        }
+        if self.is_loop || self.redraw_pending {
+            self.redraw_pending = false;
+            self.tick();
+            self.g.begin_draw();
 
-        if let Some(draw) = self.draw {
-            draw(self)
+            if let Some(draw) = self.draw {
+                draw(self)
+            }
         }
 
+        // shapes submitted outside of `draw` (e.g. in `setup`) still have to be flushed
         self.draw_frame()
     }
 
@@ -232,6 +277,29 @@ impl<S, R: Renderer> Processing<S, R> {
 
         Ok(())
     }
+
+    pub(crate) fn run_headless(
+        mut self,
+        setup: SetupFn<S, R>,
+        frames: usize,
+    ) -> anyhow::Result<Vec<RgbaImage>> {
+        setup(&mut self);
+
+        let mut images = Vec::with_capacity(frames);
+        for _ in 0..frames {
+            self.handle_draw()?;
+            images.push(self.backend.read_pixels()?);
+        }
+
+        Ok(images)
+    }
+
+    pub(crate) fn tessellate_headless(mut self, setup: SetupFn<S, R>) -> usize {
+        setup(&mut self);
+
+        let frame = self.g.frame();
+        frame.vertices.len() + frame.instances.len() + frame.sdf_vertices.len()
+    }
 }
 
 impl<S, R: Renderer + BeginShape> Processing<S, R> {
@@ -243,8 +311,22 @@ impl<S, R: Renderer + BeginShape> Processing<S, R> {
         self.g.vertex(vertex);
     }
 
-    pub fn end_shape(&mut self) {
-        self.g.end_shape();
+    /// Starts a hole in the current `GeometryKind::Polygon` shape.
+    pub fn begin_contour(&mut self) {
+        self.g.begin_contour();
+    }
+
+    pub fn end_contour(&mut self) {
+        self.g.end_contour();
+    }
+
+    /// Finishes the shape; `CloseMode::Close` also strokes the edge from the
+    /// last vertex back to the first one.
+    ///
+    /// Fails, without drawing anything, if the number of vertices does not
+    /// match the kind of shape, e.g. 6 vertices for `GeometryKind::Quads`.
+    pub fn end_shape(&mut self, close: CloseMode) -> Result<(), GeometryError> {
+        self.g.end_shape(close)
     }
 }
 
@@ -262,14 +344,92 @@ impl<S> Processing<S, GraphicsP2D> {
         self.g.line((x1, y1), (x2, y2));
     }
 
+    /// Draws a point at each of `points`, much faster than one `point` call
+    /// each.
+    pub fn points(&mut self, points: &[(f32, f32)]) {
+        self.g.points(points, None);
+    }
+
+    /// Draws a point at each of `points`, in the color of the same index in
+    /// `colors`, whatever the stroke color.
+    ///
+    /// # Panics
+    ///
+    /// If there is not one color per point.
+    pub fn colored_points(&mut self, points: &[(f32, f32)], colors: &[Color]) {
+        self.g.points(points, Some(colors));
+    }
+
+    /// Draws a line between the two ends of each of `lines`, much faster than
+    /// one `line` call each.
+    pub fn lines(&mut self, lines: &[[(f32, f32); 2]]) {
+        self.g.lines(lines, None);
+    }
+
+    /// Draws a line between the two ends of each of `lines`, in the color of
+    /// the same index in `colors`, whatever the stroke color.
+    ///
+    /// # Panics
+    ///
+    /// If there is not one color per line.
+    pub fn colored_lines(&mut self, lines: &[[(f32, f32); 2]], colors: &[Color]) {
+        self.g.lines(lines, Some(colors));
+    }
+
+    /// Strokes the open polyline through `points`, joined like a shape.
+    pub fn polyline(&mut self, points: &[(f32, f32)]) {
+        self.g.polyline(points);
+    }
+
     pub fn triangle(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x3: f32, y3: f32) {
         self.g.triangle((x1, y1), (x2, y2), (x3, y3));
     }
 
+    /// Draws a rectangle; see `rect_mode` for the meaning of the arguments.
     pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
         self.g.rect(x, y, width, height);
     }
 
+    /// Draws a quadrilateral through the four points, in order.
+    #[allow(clippy::too_many_arguments)]
+    pub fn quad(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x3: f32, y3: f32, x4: f32, y4: f32) {
+        self.g.quad((x1, y1), (x2, y2), (x3, y3), (x4, y4));
+    }
+
+    /// Draws a regular polygon with `sides` vertices at `radius` from its
+    /// center, one of them straight above it before turning the whole polygon
+    /// counter-clockwise by `rotation` radians.
+    pub fn polygon(&mut self, cx: f32, cy: f32, radius: f32, sides: usize, rotation: f32) {
+        self.g.polygon((cx, cy), radius, sides, rotation);
+    }
+
+    /// Draws a star with `points` tips at `outer` from its center, one of them
+    /// straight above it, and its inner corners at `inner` from the center.
+    pub fn star(&mut self, cx: f32, cy: f32, inner: f32, outer: f32, points: usize) {
+        self.g.star((cx, cy), inner, outer, points);
+    }
+
+    /// Draws a rectangle with rounded corners; see `rect_mode` for the meaning
+    /// of the first arguments.
+    ///
+    /// `radii` is either one radius for every corner, or the top left, top
+    /// right, bottom right and bottom left radii as an array or a tuple. They
+    /// are clamped to half the shorter side of the rectangle.
+    ///
+    /// Many filled rounded rects in a row are only drawn with instancing when
+    /// they all have the same size and radii.
+    pub fn rounded_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        radii: impl Into<CornerRadii>,
+    ) {
+        self.g.rounded_rect(x, y, width, height, radii.into());
+    }
+
+    /// Draws an ellipse; see `ellipse_mode` for the meaning of the arguments.
     pub fn ellipse(&mut self, x: f32, y: f32, width: f32, height: f32) {
         self.g.ellipse(x, y, width, height);
     }
@@ -278,13 +438,202 @@ impl<S> Processing<S, GraphicsP2D> {
         self.g.circle(x, y, diameter);
     }
 
-    pub fn arc(&mut self, x: f32, y: f32, radius: f32, start: f32, stop: f32) {
-        self.g.arc(x, y, radius, start, stop);
+    /// Draws the part of an ellipse from `start` to `stop` radians; see
+    /// `ellipse_mode` for the meaning of the other arguments.
+    ///
+    /// The angles can be given in either order and in any turn, the arc
+    /// covers at most the full ellipse. `mode` sets how its ends are joined.
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        start: f32,
+        stop: f32,
+        mode: ArcMode,
+    ) {
+        self.g.arc(x, y, width, height, start, stop, mode);
+    }
+
+    /// Sets how many segments ellipses, arcs and round strokes are split into
+    /// per full turn. By default (`None`), each gets as many as its size on
+    /// screen needs to look smooth.
+    pub fn ellipse_detail(&mut self, segments: Option<usize>) {
+        self.g.ellipse_detail(segments);
     }
 
     pub fn square(&mut self, x: f32, y: f32, size: f32) {
         self.g.square(x, y, size);
     }
+
+    /// Fills the area enclosed by `path` with the current fill rule, and
+    /// strokes its outline.
+    pub fn path(&mut self, path: &Path) {
+        self.g.path(path);
+    }
+
+    /// Draws a cubic Bezier curve from `(x1, y1)` to `(x4, y4)`, with
+    /// `(x2, y2)` and `(x3, y3)` as control points.
+    #[allow(clippy::too_many_arguments)]
+    pub fn bezier(
+        &mut self,
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        x3: f32,
+        y3: f32,
+        x4: f32,
+        y4: f32,
+    ) {
+        self.g.bezier((x1, y1), (x2, y2), (x3, y3), (x4, y4));
+    }
+
+    /// Draws a Catmull-Rom curve from `(x2, y2)` to `(x3, y3)`; the first and
+    /// last points set the direction it starts and ends with.
+    #[allow(clippy::too_many_arguments)]
+    pub fn curve(
+        &mut self,
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        x3: f32,
+        y3: f32,
+        x4: f32,
+        y4: f32,
+    ) {
+        self.g.curve((x1, y1), (x2, y2), (x3, y3), (x4, y4));
+    }
+
+    /// Draws ellipses, circles, rounded rectangles, and points and lines with
+    /// round caps with perfectly round, anti-aliased edges when `enabled`.
+    /// Each is a single quad, shaded from the distance to its outline.
+    pub fn sdf_shapes(&mut self, enabled: bool) {
+        self.g.sdf_shapes(enabled);
+    }
+
+    /// Sets how many threads tessellate the shapes of large frames, or as
+    /// many as there are cores with `None`. By default, only the thread
+    /// running the sketch. The pixels drawn are the same whatever the number.
+    pub fn tessellation_threads(&mut self, threads: Option<usize>) {
+        self.g.tessellation_threads(threads);
+    }
+
+    /// Sets how tight `curve` and `curve_vertex` curves are: 0 (the default)
+    /// draws Catmull-Rom splines, 1 straight lines between the points.
+    pub fn curve_tightness(&mut self, tightness: f32) {
+        self.g.curve_tightness(tightness);
+    }
+
+    pub fn bezier_point(&self, a: f32, b: f32, c: f32, d: f32, t: f32) -> f32 {
+        utils::bezier_point(a, b, c, d, t)
+    }
+
+    pub fn bezier_tangent(&self, a: f32, b: f32, c: f32, d: f32, t: f32) -> f32 {
+        utils::bezier_tangent(a, b, c, d, t)
+    }
+
+    pub fn curve_point(&self, a: f32, b: f32, c: f32, d: f32, t: f32) -> f32 {
+        self.g.curve_point(a, b, c, d, t)
+    }
+
+    pub fn curve_tangent(&self, a: f32, b: f32, c: f32, d: f32, t: f32) -> f32 {
+        self.g.curve_tangent(a, b, c, d, t)
+    }
+
+    // begin_shape curves
+    /// Adds a cubic Bezier curve from the previous vertex to `(x4, y4)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics outside a `GeometryKind::Polygon` shape, or before its first vertex.
+    pub fn bezier_vertex(&mut self, x2: f32, y2: f32, x3: f32, y3: f32, x4: f32, y4: f32) {
+        self.g.bezier_vertex((x2, y2), (x3, y3), (x4, y4));
+    }
+
+    /// Adds a quadratic Bezier curve from the previous vertex to `(x3, y3)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics outside a `GeometryKind::Polygon` shape, or before its first vertex.
+    pub fn quadratic_vertex(&mut self, cx: f32, cy: f32, x3: f32, y3: f32) {
+        self.g.quadratic_vertex((cx, cy), (x3, y3));
+    }
+
+    /// Adds a point of a Catmull-Rom spline. The curve goes through all the
+    /// points but the first and the last, which only shape its ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics outside a `GeometryKind::Polygon` shape.
+    pub fn curve_vertex(&mut self, x: f32, y: f32) {
+        self.g.curve_vertex((x, y));
+    }
+
+    // transform
+    pub fn translate(&mut self, x: f32, y: f32) {
+        self.g.translate(x, y);
+    }
+
+    pub fn rotate(&mut self, angle: f32) {
+        self.g.rotate(angle);
+    }
+
+    pub fn scale(&mut self, s: f32) {
+        self.g.scale(s, s);
+    }
+
+    pub fn scale_xy(&mut self, x: f32, y: f32) {
+        self.g.scale(x, y);
+    }
+
+    pub fn shear_x(&mut self, angle: f32) {
+        self.g.shear_x(angle);
+    }
+
+    pub fn shear_y(&mut self, angle: f32) {
+        self.g.shear_y(angle);
+    }
+
+    pub fn apply_matrix(&mut self, matrix: Matrix2D) {
+        self.g.apply_matrix(matrix);
+    }
+
+    pub fn reset_matrix(&mut self) {
+        self.g.reset_matrix();
+    }
+
+    pub fn matrix(&self) -> Matrix2D {
+        self.g.matrix()
+    }
+
+    pub fn push_matrix(&mut self) {
+        self.g.push_matrix();
+    }
+
+    /// # Panics
+    ///
+    /// Panics if there is no matching `push_matrix`.
+    pub fn pop_matrix(&mut self) {
+        self.g.pop_matrix();
+    }
+
+    /// Saves both the style settings and the current matrix.
+    pub fn push(&mut self) {
+        self.g.push();
+    }
+
+    /// Restores the style settings and the matrix saved by the matching `push`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no matching `push`.
+    pub fn pop(&mut self) {
+        self.g.pop();
+    }
 }
 
 impl<S> Processing<S, GraphicsP3D> {
@@ -307,7 +656,9 @@ impl<S> Processing<S, GraphicsP3D> {
 
 impl<S, R: Renderer> Processing<S, R> {
     fn event_handler(&mut self, event: Event<()>, window_target: &EventLoopWindowTarget<()>) {
-        let start_time = std::time::Instant::now();
+        if let Event::AboutToWait = event {
+            self.schedule_frame(window_target);
+        }
 
         if let Event::WindowEvent { event, .. } = event {
             match event {
@@ -354,19 +705,44 @@ impl<S, R: Renderer> Processing<S, R> {
                     }
                 }
                 WindowEvent::RedrawRequested => {
-                    println!("RedrawRequested");
-
-                    let _frame_time =
-                        std::time::Duration::from_secs_f32(1.0 / self.frame_rate as f32);
-
-                    let _ = self.handle_draw();
-
-                    let _elapsed = start_time.elapsed();
+                    // the OS also asks for redraws (resize, expose, ...), which
+                    // only show the canvas again until the next frame is due
+                    let result = if self.frame_due() {
+                        self.handle_draw()
+                    } else {
+                        self.draw_frame()
+                    };
+                    if let Err(err) = result {
+                        eprintln!("Error drawing frame: {err}");
+                    }
                 }
                 _ => (),
             }
         };
     }
+
+    /// Whether `draw` should run now: when asked to with `redraw`, or when
+    /// looping and the frame time has elapsed.
+    fn frame_due(&self) -> bool {
+        self.redraw_pending || (self.is_loop && Instant::now() >= self.next_frame)
+    }
+
+    /// Requests the next redraw once the frame time has elapsed, sleeping until then.
+    fn schedule_frame(&self, window_target: &EventLoopWindowTarget<()>) {
+        if !self.is_loop {
+            window_target.set_control_flow(ControlFlow::Wait);
+            return;
+        }
+
+        if Instant::now() >= self.next_frame {
+            if let Some(window) = self.backend.window() {
+                window.request_redraw();
+            }
+            window_target.set_control_flow(ControlFlow::Wait);
+        } else {
+            window_target.set_control_flow(ControlFlow::WaitUntil(self.next_frame));
+        }
+    }
 }
 
 // impl<T, R: Renderer> Drop for Processing<T, R> {
@@ -0,0 +1,279 @@
+use glium::index::PrimitiveType;
+use image::{Rgba, RgbaImage};
+
+use crate::{
+    core::vertex::{SdfVertex, Vertex},
+    gl_shape::{GlFrame, GlShape},
+    tess::sdf,
+    traits::Backend,
+};
+
+/// CPU backend that rasterizes `GlShape`s into an `RgbaImage`.
+///
+/// Coordinates follow the same convention as the `Painter`: the origin is the
+/// bottom-left corner and `y` grows upwards. Colors are blended the same way
+/// as the GL pipeline (premultiplied "over"), so both backends produce the
+/// same pixels for the same shapes.
+#[derive(Debug, Clone)]
+pub struct Rasterizer {
+    image: RgbaImage,
+    /// Id of the last cover-once shape that blended each pixel, like a stencil buffer
+    coverage: Vec<u32>,
+    /// Id of the cover-once shape being drawn, if any
+    cover_id: Option<u32>,
+    next_cover_id: u32,
+}
+
+impl Rasterizer {
+    pub fn new(width: u32, height: u32) -> Rasterizer {
+        Rasterizer {
+            image: RgbaImage::new(width, height),
+            coverage: vec![0; width as usize * height as usize],
+            cover_id: None,
+            next_cover_id: 1,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.image.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.image.height()
+    }
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    fn draw_shape(&mut self, shape: &GlShape, v: &[Vertex]) -> anyhow::Result<()> {
+        self.cover_id = shape.cover_once.then(|| self.new_cover_id());
+        match shape.index_type {
+            PrimitiveType::Points => v.iter().for_each(|a| self.point(a)),
+            PrimitiveType::LinesList => v.chunks_exact(2).for_each(|l| self.line(&l[0], &l[1])),
+            PrimitiveType::LineStrip => v.windows(2).for_each(|l| self.line(&l[0], &l[1])),
+            PrimitiveType::LineLoop => {
+                v.windows(2).for_each(|l| self.line(&l[0], &l[1]));
+                if let [first, .., last] = v {
+                    self.line(last, first);
+                }
+            }
+            PrimitiveType::TrianglesList => v
+                .chunks_exact(3)
+                .for_each(|t| self.triangle(&t[0], &t[1], &t[2])),
+            PrimitiveType::TriangleStrip => v
+                .windows(3)
+                .for_each(|t| self.triangle(&t[0], &t[1], &t[2])),
+            PrimitiveType::TriangleFan => {
+                if let Some((center, rest)) = v.split_first() {
+                    rest.windows(2)
+                        .for_each(|t| self.triangle(center, &t[0], &t[1]));
+                }
+            }
+            other => anyhow::bail!("unsupported primitive type: {:?}", other),
+        }
+        Ok(())
+    }
+
+    fn point(&mut self, a: &Vertex) {
+        let [x, y, _] = a.position();
+        self.blend(x.floor() as i64, y.floor() as i64, a.color());
+    }
+
+    fn line(&mut self, a: &Vertex, b: &Vertex) {
+        let [ax, ay, _] = a.position();
+        let [bx, by, _] = b.position();
+        let steps = (bx - ax).abs().max((by - ay).abs()).ceil().max(1.0) as usize;
+        for i in 0..steps {
+            let t = (i as f32 + 0.5) / steps as f32;
+            let color = lerp_color(a.color(), b.color(), t);
+            let x = ax + (bx - ax) * t;
+            let y = ay + (by - ay) * t;
+            self.blend(x.floor() as i64, y.floor() as i64, color);
+        }
+    }
+
+    fn triangle(&mut self, a: &Vertex, b: &Vertex, c: &Vertex) {
+        self.fill_triangle(a, b, c, Vertex::position, |a, b, c, weights| {
+            let [l0, l1, l2] = weights.at;
+            let (ca, cb, cc) = (a.color(), b.color(), c.color());
+            std::array::from_fn(|i| ca[i] * l0 + cb[i] * l1 + cc[i] * l2)
+        });
+    }
+
+    /// Draws a triangle of the quad of a shape drawn from its signed distance.
+    fn sdf_triangle(&mut self, a: &SdfVertex, b: &SdfVertex, c: &SdfVertex) {
+        self.fill_triangle(a, b, c, SdfVertex::position, |a, b, c, weights| {
+            let (la, lb, lc) = (a.local(), b.local(), c.local());
+            let local = |[l0, l1, l2]: [f32; 3]| {
+                std::array::from_fn(|i| la[i] * l0 + lb[i] * l1 + lc[i] * l2)
+            };
+            let step = |delta: [f32; 3]| std::array::from_fn(|i| weights.at[i] + delta[i]);
+
+            // the change to the next pixels stands in for the shader's fwidth
+            let distance = a.distance(local(weights.at));
+            let width = (a.distance(local(step(weights.dx))) - distance).abs()
+                + (a.distance(local(step(weights.dy))) - distance).abs();
+
+            let mut color = a.color();
+            color[3] *= sdf::coverage(distance, width);
+            color
+        });
+    }
+
+    /// Blends `shade` over each pixel whose center the triangle covers.
+    /// `shade` is given the vertices, possibly reordered, and the barycentric
+    /// coordinates of the pixel center in that order.
+    fn fill_triangle<V>(
+        &mut self,
+        a: &V,
+        b: &V,
+        c: &V,
+        position: impl Fn(&V) -> [f32; 3],
+        shade: impl Fn(&V, &V, &V, &Weights) -> [f32; 4],
+    ) {
+        let (pa, pb, pc) = (position(a), position(b), position(c));
+        let area = edge(pa, pb, pc);
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+        // make the triangle counter-clockwise so every inside point has
+        // positive edge functions
+        let (b, c, pb, pc, area) = if area < 0.0 {
+            (c, b, pc, pb, -area)
+        } else {
+            (b, c, pb, pc, area)
+        };
+
+        let min_x = pa[0].min(pb[0]).min(pc[0]).floor().max(0.0) as i64;
+        let min_y = pa[1].min(pb[1]).min(pc[1]).floor().max(0.0) as i64;
+        let max_x = (pa[0].max(pb[0]).max(pc[0]).ceil() as i64).min(self.width() as i64 - 1);
+        let max_y = (pa[1].max(pb[1]).max(pc[1]).ceil() as i64).min(self.height() as i64 - 1);
+
+        // how the edge functions change from one pixel to the next
+        let dx = [pb[1] - pc[1], pc[1] - pa[1], pa[1] - pb[1]].map(|d| d / area);
+        let dy = [pc[0] - pb[0], pa[0] - pc[0], pb[0] - pa[0]].map(|d| d / area);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let p = [x as f32 + 0.5, y as f32 + 0.5, 0.0];
+                let w0 = edge(pb, pc, p);
+                let w1 = edge(pc, pa, p);
+                let w2 = edge(pa, pb, p);
+                if !covers(w0, pb, pc) || !covers(w1, pc, pa) || !covers(w2, pa, pb) {
+                    continue;
+                }
+
+                let weights = Weights {
+                    at: [w0 / area, w1 / area, w2 / area],
+                    dx,
+                    dy,
+                };
+                let color = shade(a, b, c, &weights);
+                self.blend(x, y, color);
+            }
+        }
+    }
+
+    fn new_cover_id(&mut self) -> u32 {
+        if self.next_cover_id == u32::MAX {
+            self.coverage.fill(0);
+            self.next_cover_id = 1;
+        }
+        let id = self.next_cover_id;
+        self.next_cover_id += 1;
+        id
+    }
+
+    /// Blends a straight-alpha color over the pixel at `(x, y)` (bottom-left origin).
+    fn blend(&mut self, x: i64, y: i64, color: [f32; 4]) {
+        if x < 0 || y < 0 || x >= self.width() as i64 || y >= self.height() as i64 {
+            return;
+        }
+        let row = self.height() - 1 - y as u32;
+
+        if let Some(id) = self.cover_id {
+            let index = (row * self.width() + x as u32) as usize;
+            let covered = &mut self.coverage[index];
+            if *covered == id {
+                return;
+            }
+            *covered = id;
+        }
+
+        let Rgba(dst) = self.image.get_pixel_mut(x as u32, row);
+
+        let alpha = color[3].clamp(0.0, 1.0);
+        for i in 0..3 {
+            let src = color[i].clamp(0.0, 1.0) * alpha;
+            let out = src + dst[i] as f32 / 255.0 * (1.0 - alpha);
+            dst[i] = (out * 255.0).round() as u8;
+        }
+        let out = alpha + dst[3] as f32 / 255.0 * (1.0 - alpha);
+        dst[3] = (out * 255.0).round() as u8;
+    }
+}
+
+impl Backend for Rasterizer {
+    fn draw(&mut self, frame: &GlFrame, width: u32, height: u32) -> anyhow::Result<()> {
+        if width != self.width() || height != self.height() {
+            *self = Rasterizer::new(width, height);
+        }
+        for shape in &frame.shapes {
+            if shape.sdf {
+                self.cover_id = None;
+                frame.sdf_vertices[shape.range.clone()]
+                    .chunks_exact(3)
+                    .for_each(|t| self.sdf_triangle(&t[0], &t[1], &t[2]));
+                continue;
+            }
+            match &shape.instances {
+                Some(instances) => {
+                    // instanced meshes are triangle lists, placed one triangle
+                    // at a time rather than all at once
+                    self.cover_id = None;
+                    let mesh = frame.vertices(shape);
+                    for instance in &frame.instances[instances.clone()] {
+                        for t in mesh.chunks_exact(3) {
+                            let [a, b, c] = [&t[0], &t[1], &t[2]].map(|v| instance.apply(v));
+                            self.triangle(&a, &b, &c);
+                        }
+                    }
+                }
+                None => self.draw_shape(shape, frame.vertices(shape))?,
+            }
+        }
+        Ok(())
+    }
+
+    fn read_pixels(&self) -> anyhow::Result<RgbaImage> {
+        Ok(self.image.clone())
+    }
+}
+
+/// Barycentric coordinates of a pixel center in a triangle, and how much they
+/// change to the next pixel on the right and above
+struct Weights {
+    at: [f32; 3],
+    dx: [f32; 3],
+    dy: [f32; 3],
+}
+
+fn edge(a: [f32; 3], b: [f32; 3], p: [f32; 3]) -> f32 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// Inside test with a consistent tie-break for samples lying exactly on an
+/// edge, so two triangles sharing that edge never both cover the sample.
+fn covers(w: f32, a: [f32; 3], b: [f32; 3]) -> bool {
+    if w != 0.0 {
+        return w > 0.0;
+    }
+    let dx = b[0] - a[0];
+    let dy = b[1] - a[1];
+    dy > 0.0 || (dy == 0.0 && dx < 0.0)
+}
+
+fn lerp_color(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    std::array::from_fn(|i| a[i] + (b[i] - a[i]) * t)
+}
@@ -1,4 +1,6 @@
-use crate::Color;
+use std::f32::consts::TAU;
+
+use crate::{primitives::shapes_2d::CURVE_TOLERANCE, Color};
 
 #[derive(Debug)]
 pub struct WindowSettings {
@@ -17,38 +19,150 @@ impl Default for WindowSettings {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum StrokeCap {
     Butt,
     Round,
     Square,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum StrokeJoin {
     Miter,
     Round,
     Bevel,
 }
 
-#[derive(Debug)]
+/// Decides which areas of a self-intersecting polygon, or of a polygon with
+/// holes, are filled, from how many times its contours wind around them.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FillRule {
+    /// Filled where the contours wind around a non-zero number of times
+    NonZero,
+    /// Filled where the contours wind around an odd number of times
+    EvenOdd,
+}
+
+/// How the four numbers given to `rect` or `ellipse` place the shape
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ShapeMode {
+    /// Corner with the smallest coordinates, then width and height
+    Corner,
+    /// Two opposite corners
+    Corners,
+    /// Center, then width and height
+    Center,
+    /// Center, then half the width and half the height
+    Radius,
+}
+
+impl ShapeMode {
+    /// Corner with the smallest coordinates, width and height of the shape
+    /// placed by `a`, `b`, `c` and `d`.
+    pub(crate) fn bounds(self, a: f32, b: f32, c: f32, d: f32) -> (f32, f32, f32, f32) {
+        match self {
+            ShapeMode::Corner => (a, b, c, d),
+            ShapeMode::Corners => (a.min(c), b.min(d), (c - a).abs(), (d - b).abs()),
+            ShapeMode::Center => (a - c / 2.0, b - d / 2.0, c, d),
+            ShapeMode::Radius => (a - c, b - d, c * 2.0, d * 2.0),
+        }
+    }
+}
+
+/// Which outline `arc` fills and strokes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ArcMode {
+    /// The curve alone: the stroke is left open, the fill closed by the chord
+    Open,
+    /// The curve closed by a straight line between its ends
+    Chord,
+    /// The curve closed by two lines through the center, like a pie slice
+    Pie,
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct StrokeSettings {
     pub fill: Option<Color>,
+    pub fill_rule: FillRule,
 
     pub stroke: Option<Color>,
     pub stroke_weight: f32,
     pub stroke_cap: StrokeCap,
     pub stroke_join: StrokeJoin,
+
+    pub rect_mode: ShapeMode,
+    pub ellipse_mode: ShapeMode,
+}
+
+impl StrokeSettings {
+    /// The stroke style, with round parts detailed for an untransformed canvas
+    pub fn style(&self) -> StrokeStyle {
+        StrokeStyle {
+            weight: self.stroke_weight,
+            cap: self.stroke_cap,
+            join: self.stroke_join,
+            detail: EllipseDetail::Tolerance(CURVE_TOLERANCE),
+        }
+    }
 }
 
 impl Default for StrokeSettings {
     fn default() -> Self {
         StrokeSettings {
             fill: Some(Color::rgb(255, 255, 255)),
+            fill_rule: FillRule::NonZero,
             stroke: Some(Color::rgb(0, 0, 0)),
             stroke_weight: 1.0,
             stroke_cap: StrokeCap::Butt,
             stroke_join: StrokeJoin::Miter,
+            rect_mode: ShapeMode::Corner,
+            ellipse_mode: ShapeMode::Center,
+        }
+    }
+}
+
+/// The part of the settings that shapes the stroke outline
+#[derive(Debug, Clone, Copy)]
+pub struct StrokeStyle {
+    pub weight: f32,
+    pub cap: StrokeCap,
+    pub join: StrokeJoin,
+    /// How finely round caps and joins are drawn
+    pub detail: EllipseDetail,
+}
+
+/// How many straight segments circles, ellipses and their arcs are split into
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EllipseDetail {
+    /// As few as keep the outline within this distance of the real curve
+    Tolerance(f32),
+    /// This many for a full turn, whatever the size
+    Segments(usize),
+}
+
+impl EllipseDetail {
+    const MIN_SEGMENTS: usize = 4;
+    const MAX_SEGMENTS: usize = 1024;
+
+    /// Segments for a full turn around an ellipse whose larger radius is `radius`
+    pub(crate) fn segments(self, radius: f32) -> usize {
+        match self {
+            EllipseDetail::Segments(segments) => segments.max(3),
+            EllipseDetail::Tolerance(tolerance) => {
+                // the middle of each segment is `tolerance` away from the curve
+                let ratio = tolerance / radius.abs();
+                if ratio >= 1.0 || ratio.is_nan() {
+                    return Self::MIN_SEGMENTS;
+                }
+                let step = 2.0 * (1.0 - ratio).acos();
+                ((TAU / step).ceil() as usize).clamp(Self::MIN_SEGMENTS, Self::MAX_SEGMENTS)
+            }
         }
     }
+
+    /// Segments for an arc of `sweep` radians, at least one
+    pub(crate) fn arc_segments(self, radius: f32, sweep: f32) -> usize {
+        let segments = self.segments(radius) as f32 * sweep.abs() / TAU;
+        (segments.ceil() as usize).max(1)
+    }
 }
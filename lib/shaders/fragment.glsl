@@ -8,5 +8,6 @@ precision mediump int;
 in vec4 vertex_color;
 
 void main() {
-    gl_FragColor = vertex_color;
-}
\ No newline at end of file
+    // premultiplied alpha, matching the blend function and the rasterizer
+    gl_FragColor = vec4(vertex_color.rgb * vertex_color.a, vertex_color.a);
+}
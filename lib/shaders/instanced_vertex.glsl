@@ -0,0 +1,19 @@
+#version 140
+
+uniform mat4 projection;
+
+in vec3 position;
+in vec4 color;
+
+// rows of the 2D transform placing this instance
+in vec3 transform_x;
+in vec3 transform_y;
+in vec4 instance_color;
+
+out vec4 vertex_color;
+
+void main() {
+    vec3 local = vec3(position.xy, 1.0);
+    vertex_color = color * instance_color;
+    gl_Position = projection * vec4(dot(transform_x, local), dot(transform_y, local), position.z, 1.0);
+}
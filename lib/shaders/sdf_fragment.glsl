@@ -0,0 +1,46 @@
+#version 140
+
+#ifdef GL_ES
+precision mediump float;
+precision mediump int;
+#endif
+
+in vec4 vertex_color;
+in vec2 vertex_local;
+flat in vec2 vertex_size;
+flat in vec4 vertex_radii;
+flat in float vertex_half_weight;
+flat in float vertex_ellipse;
+
+// signed distance to the rectangle spanning `b` on each side of the origin,
+// with its corners rounded by `r`: top right, bottom right, top left, bottom left
+float rounded_box(vec2 p, vec2 b, vec4 r) {
+    r.xy = (p.x > 0.0) ? r.xy : r.zw;
+    r.x = (p.y > 0.0) ? r.x : r.y;
+    vec2 q = abs(p) - b + r.x;
+    return min(max(q.x, q.y), 0.0) + length(max(q, 0.0)) - r.x;
+}
+
+// approximate signed distance to the ellipse of semi-axes `ab`, exact on it
+float ellipse(vec2 p, vec2 ab) {
+    float k0 = length(p / ab);
+    float k1 = length(p / (ab * ab));
+    return k1 == 0.0 ? -min(ab.x, ab.y) : k0 * (k0 - 1.0) / k1;
+}
+
+void main() {
+    float d = vertex_ellipse > 0.5
+        ? ellipse(vertex_local, vertex_size)
+        : rounded_box(vertex_local, vertex_size, vertex_radii);
+    if (vertex_half_weight > 0.0) {
+        d = abs(d) - vertex_half_weight;
+    }
+
+    // fade out over the pixel the edge crosses
+    float width = fwidth(d);
+    float coverage = width > 0.0 ? clamp(0.5 - d / width, 0.0, 1.0) : step(d, 0.0);
+
+    // premultiplied alpha, matching the blend function and the rasterizer
+    float alpha = vertex_color.a * coverage;
+    gl_FragColor = vec4(vertex_color.rgb * alpha, alpha);
+}
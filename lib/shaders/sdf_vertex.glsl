@@ -0,0 +1,30 @@
+#version 140
+
+uniform mat4 projection;
+
+in vec3 position;
+in vec4 color;
+
+// the shape, in its own space centered on it
+in vec2 local;
+in vec2 size;
+in vec4 radii;
+in float half_weight;
+in float ellipse;
+
+out vec4 vertex_color;
+out vec2 vertex_local;
+flat out vec2 vertex_size;
+flat out vec4 vertex_radii;
+flat out float vertex_half_weight;
+flat out float vertex_ellipse;
+
+void main() {
+    vertex_color = color;
+    vertex_local = local;
+    vertex_size = size;
+    vertex_radii = radii;
+    vertex_half_weight = half_weight;
+    vertex_ellipse = ellipse;
+    gl_Position = projection * vec4(position, 1.0);
+}
@@ -0,0 +1,146 @@
+use crate::settings::FillRule;
+
+/// A vertex the polygon filler can place anywhere along an edge.
+pub trait FillVertex: Copy {
+    fn xy(&self) -> (f32, f32);
+
+    /// The vertex a fraction `t` of the way from `self` to `other`.
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+/// Slabs thinner than this are skipped
+const EPSILON: f32 = 1e-6;
+
+/// Non-horizontal polygon edge, stored bottom to top
+struct Edge<V> {
+    bottom: V,
+    top: V,
+    y0: f32,
+    y1: f32,
+    /// +1 if the contour goes up along this edge, -1 if it goes down
+    winding: i32,
+}
+
+impl<V: FillVertex> Edge<V> {
+    fn new(a: V, b: V) -> Option<Edge<V>> {
+        let (ay, by) = (a.xy().1, b.xy().1);
+        if ay == by || !ay.is_finite() || !by.is_finite() {
+            return None;
+        }
+        let (bottom, top, winding) = if ay < by { (a, b, 1) } else { (b, a, -1) };
+        Some(Edge {
+            bottom,
+            top,
+            y0: ay.min(by),
+            y1: ay.max(by),
+            winding,
+        })
+    }
+
+    fn at(&self, y: f32) -> V {
+        self.bottom
+            .lerp(&self.top, (y - self.y0) / (self.y1 - self.y0))
+    }
+
+    fn x_at(&self, y: f32) -> f32 {
+        let (x0, _) = self.bottom.xy();
+        let (x1, _) = self.top.xy();
+        x0 + (x1 - x0) * (y - self.y0) / (self.y1 - self.y0)
+    }
+
+    /// Height at which `self` and `other` cross, strictly inside both edges.
+    fn crossing(&self, other: &Edge<V>) -> Option<f32> {
+        let low = self.y0.max(other.y0);
+        let high = self.y1.min(other.y1);
+        if high <= low {
+            return None;
+        }
+        let d_low = self.x_at(low) - other.x_at(low);
+        let d_high = self.x_at(high) - other.x_at(high);
+        if d_low * d_high >= 0.0 {
+            return None;
+        }
+        let y = low + (high - low) * d_low / (d_low - d_high);
+        (y > low && y < high).then_some(y)
+    }
+}
+
+/// Triangulates the area enclosed by `contours` according to `rule`.
+///
+/// Each contour is implicitly closed. The contours may be concave, cross
+/// themselves or each other, and contours inside others make holes (with
+/// `FillRule::NonZero`, only when they wind the other way).
+///
+/// The plane is cut into horizontal slabs at every vertex and crossing, so
+/// within a slab the edges never cross and the filled spans are trapezoids.
+/// The returned list holds three vertices per triangle.
+pub fn polygon<V: FillVertex>(contours: &[Vec<V>], rule: FillRule) -> Vec<V> {
+    let mut edges: Vec<Edge<V>> = contours
+        .iter()
+        .filter(|contour| contour.len() > 2)
+        .flat_map(|contour| {
+            let next = contour.iter().cycle().skip(1);
+            contour
+                .iter()
+                .zip(next)
+                .filter_map(|(&a, &b)| Edge::new(a, b))
+        })
+        .collect();
+    edges.sort_by(|a, b| a.y0.total_cmp(&b.y0));
+
+    let mut ys: Vec<f32> = edges.iter().flat_map(|edge| [edge.y0, edge.y1]).collect();
+    for (i, a) in edges.iter().enumerate() {
+        for b in edges[i + 1..].iter().take_while(|b| b.y0 < a.y1) {
+            ys.extend(a.crossing(b));
+        }
+    }
+    ys.sort_by(f32::total_cmp);
+    ys.dedup();
+
+    let mut triangles = Vec::new();
+    let mut active: Vec<&Edge<V>> = Vec::new();
+    let mut next_edge = 0;
+    for slab in ys.windows(2) {
+        let (y0, y1) = (slab[0], slab[1]);
+        while next_edge < edges.len() && edges[next_edge].y0 <= y0 {
+            active.push(&edges[next_edge]);
+            next_edge += 1;
+        }
+        active.retain(|edge| edge.y1 > y0);
+        if y1 - y0 < EPSILON {
+            continue;
+        }
+
+        let middle = (y0 + y1) / 2.0;
+        active.sort_by(|a, b| a.x_at(middle).total_cmp(&b.x_at(middle)));
+
+        let mut winding = 0;
+        let mut left = None;
+        for edge in &active {
+            let was_inside = rule.is_inside(winding);
+            winding += edge.winding;
+            match (was_inside, rule.is_inside(winding)) {
+                (false, true) => left = Some(edge),
+                (true, false) => {
+                    if let Some(left) = left.take() {
+                        let (a, b) = (left.at(y0), edge.at(y0));
+                        let (c, d) = (edge.at(y1), left.at(y1));
+                        triangles.extend([a, b, c, a, c, d]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    triangles
+}
+
+impl FillRule {
+    fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
@@ -1,119 +1,106 @@
 use crate::primitives::shapes_2d::Point;
 
 pub mod gl_triangle {
-    use std::f32::consts::PI;
+    use std::f32::consts::{FRAC_PI_4, SQRT_2};
 
     use super::*;
     use crate::{
         primitives::shapes_2d::Edges,
+        settings::{ArcMode, StrokeStyle},
         tess::{
-            primitives::{GlTriangle, GlTriangleVec},
-            utils::{extend_line, line_angle},
+            primitives::{EllipseFan, GlTriangle, GlTriangleVec},
+            stroke,
         },
         StrokeCap,
     };
 
-    pub fn point(point: Point, weight: f32, cap: StrokeCap) -> GlTriangleVec {
-        let half_weight = weight / 2.0;
+    pub fn point(point: Point, style: StrokeStyle) -> EllipseFan {
+        let half_weight = style.weight / 2.0;
 
-        match cap {
-            StrokeCap::Butt => GlTriangleVec::new(),
-            StrokeCap::Round => circle(point, half_weight, 20),
-            StrokeCap::Square => quad(
-                Point::new((point.x - half_weight, point.y - half_weight)),
-                Point::new((point.x + half_weight, point.y - half_weight)),
-                Point::new((point.x + half_weight, point.y + half_weight)),
-                Point::new((point.x - half_weight, point.y + half_weight)),
-            ),
-        }
-    }
-
-    pub fn line_no_cap(a: Point, b: Point, weight: f32) -> GlTriangleVec {
-        let half_weight = weight / 2.0;
-        let angle = line_angle(a, b) + PI / 2.0;
-        let ex = half_weight * angle.cos();
-        let ey = half_weight * angle.sin();
-
-        quad(
-            Point::new((a.x - ex, a.y - ey)),
-            Point::new((b.x - ex, b.y - ey)),
-            Point::new((b.x + ex, b.y + ey)),
-            Point::new((a.x + ex, a.y + ey)),
-        )
-    }
-
-    pub fn line(a: Point, b: Point, weight: f32, cap: StrokeCap) -> GlTriangleVec {
-        match cap {
-            StrokeCap::Butt => line_no_cap(a, b, weight),
-            StrokeCap::Round => GlTriangleVec::new()
-                .and(circle(a, weight / 2.0, 20))
-                .and(circle(b, weight / 2.0, 20))
-                .and(line_no_cap(a, b, weight)),
+        match style.cap {
+            StrokeCap::Butt => EllipseFan::new(point, (0.0, 0.0), 0.0, 0),
+            StrokeCap::Round => circle(point, half_weight, style.detail.segments(half_weight)),
+            // a square is a four segment fan through its corners
             StrokeCap::Square => {
-                let (a, b) = extend_line(a, b, weight);
-                line_no_cap(a, b, weight)
+                let radius = half_weight * SQRT_2;
+                EllipseFan::new(point, (radius, radius), FRAC_PI_4, 4)
             }
         }
     }
 
+    pub fn line(a: Point, b: Point, style: StrokeStyle) -> GlTriangleVec {
+        // a single segment has no joins
+        stroke::polyline(&[a, b], false, style)
+    }
+
     pub fn triangle(a: Point, b: Point, c: Point) -> GlTriangle {
         GlTriangle::new(a, b, c)
     }
 
+    /// Splits the quad along whichever diagonal lies inside it, so that
+    /// concave quads are filled too.
     pub fn quad(a: Point, b: Point, c: Point, d: Point) -> GlTriangleVec {
-        GlTriangleVec::from_elem(vec![GlTriangle::new(a, b, c), GlTriangle::new(a, c, d)])
+        let turn =
+            |p: Point, q: Point, r: Point| (q.x - p.x) * (r.y - q.y) - (q.y - p.y) * (r.x - q.x);
+        let area = turn(a, b, c) + turn(c, d, a);
+        if turn(a, b, c) * area < 0.0 || turn(c, d, a) * area < 0.0 {
+            // `b` or `d` is the reflex corner
+            GlTriangleVec::from_elem(vec![GlTriangle::new(b, c, d), GlTriangle::new(b, d, a)])
+        } else {
+            GlTriangleVec::from_elem(vec![GlTriangle::new(a, b, c), GlTriangle::new(a, c, d)])
+        }
     }
 
-    pub fn ellipse(center: Point, axes: (f32, f32), segments: usize) -> GlTriangleVec {
-        let step = 2.0 * PI / segments as f32;
-        GlTriangleVec::from_elem(
-            (0..segments)
-                .map(|i| (i as f32 * step, (i + 1) as f32 * step))
-                .map(|(a, b)| {
-                    GlTriangle::new(
-                        Point::new((center.x, center.y)),
-                        Point::new((center.x + a.cos() * axes.0, center.y + a.sin() * axes.1)),
-                        Point::new((center.x + b.cos() * axes.0, center.y + b.sin() * axes.1)),
-                    )
-                })
-                .collect(),
-        )
+    pub fn ellipse(center: Point, axes: (f32, f32), segments: usize) -> EllipseFan {
+        EllipseFan::new(center, axes, 0.0, segments)
+    }
+
+    /// `segments + 1` points along the arc, from `start` turning by `sweep`
+    pub fn arc_points(
+        center: Point,
+        axes: (f32, f32),
+        start: f32,
+        sweep: f32,
+        segments: usize,
+    ) -> Vec<Point> {
+        (0..=segments)
+            .map(|i| start + sweep * i as f32 / segments as f32)
+            .map(|a| Point::new((center.x + a.cos() * axes.0, center.y + a.sin() * axes.1)))
+            .collect()
     }
 
+    /// The part of an ellipse between an arc and either its center
+    /// (`ArcMode::Pie`) or its chord
     pub fn ellipse_arc(
         center: Point,
         axes: (f32, f32),
         start: f32,
-        end: f32,
+        sweep: f32,
         segments: usize,
+        mode: ArcMode,
     ) -> GlTriangleVec {
-        let step = (end - start) / segments as f32;
+        let points = arc_points(center, axes, start, sweep, segments);
+        let pivot = match mode {
+            ArcMode::Pie => center,
+            ArcMode::Open | ArcMode::Chord => points[0],
+        };
         GlTriangleVec::from_elem(
-            (0..segments)
-                .map(|i| (start + i as f32 * step, start + (i + 1) as f32 * step))
-                .map(|(a, b)| {
-                    GlTriangle::new(
-                        Point::new((center.x, center.y)),
-                        Point::new((center.x + a.cos() * axes.0, center.y + a.sin() * axes.1)),
-                        Point::new((center.x + b.cos() * axes.0, center.y + b.sin() * axes.1)),
-                    )
-                })
+            points
+                .windows(2)
+                .map(|edge| GlTriangle::new(pivot, edge[0], edge[1]))
                 .collect(),
         )
     }
 
-    pub fn circle(center: Point, radius: f32, segments: usize) -> GlTriangleVec {
+    pub fn circle(center: Point, radius: f32, segments: usize) -> EllipseFan {
         ellipse(center, (radius, radius), segments)
     }
 
-    pub fn arc(center: Point, radius: f32, start: f32, end: f32, segments: usize) -> GlTriangleVec {
-        ellipse_arc(center, (radius, radius), start, end, segments)
-    }
-
-    pub fn stroke(edges: Edges, weight: f32) -> GlTriangleVec {
+    pub fn stroke(edges: Edges, style: StrokeStyle) -> GlTriangleVec {
         edges
+            .polylines()
             .into_iter()
-            .map(|edge| line(edge.a, edge.b, weight, StrokeCap::Round))
+            .map(|(points, closed)| stroke::polyline(&points, closed, style))
             .fold(GlTriangleVec::new(), |acc, x| acc.and(x))
     }
 }
@@ -1,5 +1,7 @@
+pub mod fill;
 pub mod fns;
 pub mod primitives;
+pub mod sdf;
+pub mod stroke;
 pub mod tessellate;
 pub mod tessellator;
-pub mod utils;
@@ -1,3 +1,5 @@
+use std::{array, iter::Flatten, vec};
+
 use crate::primitives::shapes_2d::Point;
 
 pub struct GlTriangle {
@@ -14,10 +16,10 @@ impl GlTriangle {
 
 impl IntoIterator for GlTriangle {
     type Item = Point;
-    type IntoIter = std::vec::IntoIter<Point>;
+    type IntoIter = array::IntoIter<Point, 3>;
 
     fn into_iter(self) -> Self::IntoIter {
-        vec![self.a, self.b, self.c].into_iter()
+        [self.a, self.b, self.c].into_iter()
     }
 }
 
@@ -40,13 +42,84 @@ impl GlTriangleVec {
 
 impl IntoIterator for GlTriangleVec {
     type Item = Point;
-    type IntoIter = std::vec::IntoIter<Point>;
+    type IntoIter = Flatten<vec::IntoIter<GlTriangle>>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0
-            .into_iter()
-            .flat_map(|triangle| triangle.into_iter())
-            .collect::<Vec<Point>>()
-            .into_iter()
+        self.0.into_iter().flatten()
+    }
+}
+
+impl FromIterator<GlTriangle> for GlTriangleVec {
+    fn from_iter<I: IntoIterator<Item = GlTriangle>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// Triangles from the center of an ellipse to each segment of its outline,
+/// computed as they are iterated rather than stored.
+pub struct EllipseFan {
+    center: Point,
+    axes: (f32, f32),
+    /// Direction of the first point of the outline
+    first: (f32, f32),
+    /// Direction of the next point of the outline
+    direction: (f32, f32),
+    /// Cosine and sine of the angle between consecutive points
+    step: (f32, f32),
+    /// Segments left to iterate
+    remaining: usize,
+    /// Where the next segment starts
+    previous: Point,
+}
+
+impl EllipseFan {
+    /// `segments` triangles, the first segment starting at the `start` angle
+    pub fn new(center: Point, axes: (f32, f32), start: f32, segments: usize) -> EllipseFan {
+        let step = std::f32::consts::TAU / segments.max(1) as f32;
+        let first = (start.cos(), start.sin());
+        EllipseFan {
+            center,
+            axes,
+            first,
+            direction: first,
+            step: (step.cos(), step.sin()),
+            remaining: segments,
+            previous: Point::new((center.x + first.0 * axes.0, center.y + first.1 * axes.1)),
+        }
     }
 }
+
+impl Iterator for EllipseFan {
+    type Item = GlTriangle;
+
+    fn next(&mut self) -> Option<GlTriangle> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        // turning the direction step by step is much cheaper than calling
+        // cos and sin for each point; the last one lands exactly on the first
+        let (x, y) = self.direction;
+        let (cos, sin) = self.step;
+        self.direction = if self.remaining == 0 {
+            self.first
+        } else {
+            (x * cos - y * sin, x * sin + y * cos)
+        };
+
+        let end = Point::new((
+            self.center.x + self.direction.0 * self.axes.0,
+            self.center.y + self.direction.1 * self.axes.1,
+        ));
+        let triangle = GlTriangle::new(self.center, self.previous, end);
+        self.previous = end;
+        Some(triangle)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for EllipseFan {}
@@ -0,0 +1,211 @@
+use crate::{core::vertex::SdfVertex, primitives::shapes_2d::CornerRadii, Color, Matrix2D};
+
+/// Device pixels around a shape's outline left inside its quad, for the
+/// anti-aliased edge to fade out
+const EDGE_PIXELS: f32 = 2.0;
+
+/// Outline of a shape centered on the origin of its own space, drawn from its
+/// signed distance by the SDF fragment shader
+#[derive(Debug, Clone, Copy)]
+pub enum SdfShape {
+    Ellipse {
+        axes: (f32, f32),
+    },
+    /// Rectangle spanning `half_size` on each side of the origin, with its
+    /// corners rounded by `radii`
+    RoundedBox {
+        half_size: (f32, f32),
+        radii: CornerRadii,
+    },
+}
+
+impl SdfShape {
+    /// An ellipse, as a rounded box when it is a circle or a line, whose
+    /// distance is exact
+    pub fn ellipse(axes: (f32, f32)) -> SdfShape {
+        let (a, b) = (axes.0.abs(), axes.1.abs());
+        if a == b || a == 0.0 || b == 0.0 {
+            SdfShape::RoundedBox {
+                half_size: (a, b),
+                radii: CornerRadii::from(a.min(b)),
+            }
+        } else {
+            SdfShape::Ellipse { axes: (a, b) }
+        }
+    }
+
+    /// A line from `a` to `b` with round caps, `weight` wide, and the matrix
+    /// placing it
+    pub fn line(a: (f32, f32), b: (f32, f32), weight: f32) -> (SdfShape, Matrix2D) {
+        let half = weight / 2.0;
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let shape = SdfShape::RoundedBox {
+            half_size: (dx.hypot(dy) / 2.0 + half, half),
+            radii: CornerRadii::from(half),
+        };
+        let center = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        let placement =
+            Matrix2D::translation(center.0, center.1) * Matrix2D::rotation(dy.atan2(dx));
+        (shape, placement)
+    }
+
+    /// Whether the shape has no area, so that filling it draws nothing
+    pub fn is_empty(self) -> bool {
+        let (a, b) = match self {
+            SdfShape::Ellipse { axes } => axes,
+            SdfShape::RoundedBox { half_size, .. } => half_size,
+        };
+        a <= 0.0 || b <= 0.0
+    }
+
+    /// Two triangles covering the shape placed by `matrix`, filled with
+    /// `color`, or with its outline stroked `weight` wide when `weight` is
+    /// positive.
+    pub fn quad(self, matrix: Matrix2D, color: Color, weight: f32) -> [SdfVertex; 6] {
+        let half_weight = weight.max(0.0) / 2.0;
+        let (size, radii, ellipse) = match self {
+            SdfShape::Ellipse { axes } => (axes, [0.0; 4], true),
+            SdfShape::RoundedBox { half_size, radii } => {
+                let radii = [
+                    radii.top_right,
+                    radii.bottom_right,
+                    radii.top_left,
+                    radii.bottom_left,
+                ];
+                (half_size, radii, false)
+            }
+        };
+
+        // from one device pixel to the next, a local coordinate changes by at
+        // most the length of its row of the inverse
+        let pixel = match matrix.inverse() {
+            Some(inverse) => (
+                inverse.m00.hypot(inverse.m01),
+                inverse.m10.hypot(inverse.m11),
+            ),
+            None => (1.0, 1.0),
+        };
+        let x = size.0 + half_weight + EDGE_PIXELS * pixel.0;
+        let y = size.1 + half_weight + EDGE_PIXELS * pixel.1;
+
+        let [a, b, c, d] = [(-x, -y), (x, -y), (x, y), (-x, y)].map(|local| {
+            let position = matrix.apply_xy(local.0, local.1);
+            SdfVertex::new(position, color, local, size, radii, half_weight, ellipse)
+        });
+        [a, b, c, a, c, d]
+    }
+}
+
+/// Signed distance from `p` to the rectangle spanning `half_size` on each
+/// side of the origin, with its corners rounded by `radii`: top right, bottom
+/// right, top left and bottom left.
+pub fn rounded_box(p: [f32; 2], half_size: [f32; 2], radii: [f32; 4]) -> f32 {
+    let radius = match (p[0] > 0.0, p[1] > 0.0) {
+        (true, true) => radii[0],
+        (true, false) => radii[1],
+        (false, true) => radii[2],
+        (false, false) => radii[3],
+    };
+    let q = [
+        p[0].abs() - half_size[0] + radius,
+        p[1].abs() - half_size[1] + radius,
+    ];
+    let outside = q[0].max(0.0).hypot(q[1].max(0.0));
+    q[0].max(q[1]).min(0.0) + outside - radius
+}
+
+/// Approximate signed distance from `p` to the ellipse of semi-axes `axes`
+/// centered on the origin, exact on its outline
+pub fn ellipse(p: [f32; 2], axes: [f32; 2]) -> f32 {
+    let k0 = (p[0] / axes[0]).hypot(p[1] / axes[1]);
+    let k1 = (p[0] / (axes[0] * axes[0])).hypot(p[1] / (axes[1] * axes[1]));
+    if k1 == 0.0 {
+        -axes[0].min(axes[1])
+    } else {
+        k0 * (k0 - 1.0) / k1
+    }
+}
+
+/// How much of a pixel is covered, for a distance `distance` to the edge of
+/// the shape changing by `width` from one pixel to the next.
+pub fn coverage(distance: f32, width: f32) -> f32 {
+    if width > 0.0 {
+        (0.5 - distance / width).clamp(0.0, 1.0)
+    } else if distance <= 0.0 {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn rounded_box_distance() {
+        let sharp = |p| rounded_box(p, [10.0, 5.0], [0.0; 4]);
+        assert_near(sharp([0.0, 0.0]), -5.0);
+        assert_near(sharp([-8.0, 1.0]), -2.0);
+        assert_near(sharp([10.0, 0.0]), 0.0);
+        assert_near(sharp([0.0, -5.0]), 0.0);
+        assert_near(sharp([13.0, 9.0]), 5.0);
+        assert_near(sharp([-13.0, -9.0]), 5.0);
+
+        // only the top right corner is rounded
+        let rounded = |p| rounded_box(p, [10.0, 10.0], [4.0, 0.0, 0.0, 0.0]);
+        assert_near(rounded([10.0, 10.0]), 32f32.sqrt() - 4.0);
+        let on_arc = 6.0 + 4.0 * std::f32::consts::FRAC_1_SQRT_2;
+        assert_near(rounded([on_arc, on_arc]), 0.0);
+        assert_near(rounded([0.0, 0.0]), -10.0);
+        assert_near(rounded([10.0, -10.0]), 0.0);
+        assert_near(rounded([-10.0, 10.0]), 0.0);
+    }
+
+    #[test]
+    fn rounded_box_distance_with_degenerate_axis() {
+        // a segment from (-10, 0) to (10, 0)
+        let segment = |p| rounded_box(p, [10.0, 0.0], [0.0; 4]);
+        assert_near(segment([0.0, 0.0]), 0.0);
+        assert_near(segment([0.0, 3.0]), 3.0);
+        assert_near(segment([0.0, -3.0]), 3.0);
+        assert_near(segment([13.0, 4.0]), 5.0);
+    }
+
+    #[test]
+    fn ellipse_distance() {
+        let axes = [10.0, 5.0];
+        assert_near(ellipse([0.0, 0.0], axes), -5.0);
+        assert_near(ellipse([0.0, 3.0], axes), -2.0);
+        assert_near(ellipse([10.0, 0.0], axes), 0.0);
+        assert_near(ellipse([0.0, -5.0], axes), 0.0);
+        let angle: f32 = 0.7;
+        assert_near(ellipse([10.0 * angle.cos(), 5.0 * angle.sin()], axes), 0.0);
+        assert_near(ellipse([12.0, 0.0], axes), 2.0);
+        assert_near(ellipse([0.0, -8.0], axes), 3.0);
+    }
+
+    #[test]
+    fn degenerate_shapes_are_empty() {
+        assert!(SdfShape::ellipse((10.0, 0.0)).is_empty());
+        assert!(SdfShape::ellipse((0.0, 0.0)).is_empty());
+        assert!(SdfShape::line((0.0, 0.0), (10.0, 0.0), 0.0).0.is_empty());
+        assert!(!SdfShape::ellipse((10.0, 1.0)).is_empty());
+        assert!(!SdfShape::line((5.0, 5.0), (5.0, 5.0), 2.0).0.is_empty());
+    }
+
+    #[test]
+    fn quad_pads_each_axis_by_device_pixels() {
+        let circle = SdfShape::ellipse((10.0, 10.0));
+        let quad = circle.quad(Matrix2D::scaling(8.0, 0.5), Color::WHITE, 0.0);
+        assert_eq!(quad[0].position(), [-82.0, -7.0, 0.0]);
+        assert_eq!(quad[2].position(), [82.0, 7.0, 0.0]);
+    }
+}
@@ -0,0 +1,199 @@
+use std::f32::consts::PI;
+
+use crate::{
+    primitives::shapes_2d::Point,
+    settings::{EllipseDetail, StrokeJoin, StrokeStyle},
+    tess::{
+        fns::gl_triangle,
+        primitives::{GlTriangle, GlTriangleVec},
+    },
+    StrokeCap, Vector2D,
+};
+
+/// Ratio between the miter length and the stroke weight above which a miter
+/// join is drawn as a bevel. Same default as Java2D, which Processing uses.
+pub const MITER_LIMIT: f32 = 10.0;
+
+/// Points closer than this are considered the same point
+const EPSILON: f32 = 1e-3;
+
+/// Tessellates the outline of a polyline, with joins between consecutive
+/// segments and caps at both ends when it is open.
+pub fn polyline(points: &[Point], closed: bool, style: StrokeStyle) -> GlTriangleVec {
+    let mut points = dedup(points);
+    if closed && points.len() > 2 && same(points[0], points[points.len() - 1]) {
+        points.pop();
+    }
+
+    match points.len() {
+        0 => return GlTriangleVec::new(),
+        1 => return gl_triangle::point(points[0].into(), style).collect(),
+        _ => {}
+    }
+
+    let closed = closed && points.len() > 2;
+    let half = style.weight / 2.0;
+    let n = points.len();
+    let mut triangles = Vec::new();
+
+    let first_dir = direction(points[0], points[1]);
+    let last_dir = direction(points[n - 2], points[n - 1]);
+    if !closed && style.cap == StrokeCap::Square {
+        points[0] -= first_dir * half;
+        points[n - 1] += last_dir * half;
+    }
+
+    let segments = if closed { n } else { n - 1 };
+    for i in 0..segments {
+        segment(&mut triangles, points[i], points[(i + 1) % n], half);
+    }
+
+    let joins = if closed { 0..n } else { 1..n - 1 };
+    for i in joins {
+        let prev = points[(i + n - 1) % n];
+        let next = points[(i + 1) % n];
+        let p = points[i];
+        join(
+            &mut triangles,
+            p,
+            direction(prev, p),
+            direction(p, next),
+            half,
+            style,
+        );
+    }
+
+    if !closed && style.cap == StrokeCap::Round {
+        // half discs turning from one side of the stroke to the other, around the end
+        let detail = style.detail;
+        fan(
+            &mut triangles,
+            points[0],
+            left_normal(first_dir),
+            PI,
+            half,
+            detail,
+        );
+        fan(
+            &mut triangles,
+            points[n - 1],
+            left_normal(last_dir) * -1.0,
+            PI,
+            half,
+            detail,
+        );
+    }
+
+    GlTriangleVec::from_elem(triangles)
+}
+
+fn segment(triangles: &mut Vec<GlTriangle>, a: Vector2D, b: Vector2D, half: f32) {
+    let offset = left_normal(direction(a, b)) * half;
+    push_quad(triangles, a - offset, b - offset, b + offset, a + offset);
+}
+
+/// Fills the gap on the outer side of the corner at `p`, between a segment
+/// arriving with direction `d0` and one leaving with direction `d1`.
+fn join(
+    triangles: &mut Vec<GlTriangle>,
+    p: Vector2D,
+    d0: Vector2D,
+    d1: Vector2D,
+    half: f32,
+    style: StrokeStyle,
+) {
+    let cross = d0.x * d1.y - d0.y * d1.x;
+    let dot = d0.dot(d1);
+    if cross.abs() < EPSILON && dot > 0.0 {
+        // straight continuation, the segments already meet
+        return;
+    }
+
+    // the gap is on the right of a left turn and on the left of a right turn
+    let side = if cross > 0.0 { -1.0 } else { 1.0 };
+    let o0 = left_normal(d0) * side;
+    let o1 = left_normal(d1) * side;
+
+    match style.join {
+        StrokeJoin::Bevel => push_triangle(triangles, p, p + o0 * half, p + o1 * half),
+        StrokeJoin::Miter => {
+            let bisector = o0 + o1;
+            // cosine of half the angle between the two offsets
+            let cos_half = bisector.magnitude() / 2.0;
+            if cos_half < EPSILON || 1.0 / cos_half > MITER_LIMIT {
+                push_triangle(triangles, p, p + o0 * half, p + o1 * half);
+            } else {
+                let miter = p + bisector.normalized() * (half / cos_half);
+                push_triangle(triangles, p, p + o0 * half, miter);
+                push_triangle(triangles, p, miter, p + o1 * half);
+            }
+        }
+        StrokeJoin::Round => {
+            let mut sweep = (o0.x * o1.y - o0.y * o1.x).atan2(o0.dot(o1));
+            if cross.abs() < EPSILON {
+                // the path turns back on itself: bulge forward, past the corner
+                sweep = if left_normal(o0).dot(d0) > 0.0 {
+                    PI
+                } else {
+                    -PI
+                };
+            }
+            fan(triangles, p, o0, sweep, half, style.detail);
+        }
+    }
+}
+
+/// Circular sector around `center` starting at the unit vector `from` and
+/// turning by `sweep` radians (counter-clockwise when positive).
+fn fan(
+    triangles: &mut Vec<GlTriangle>,
+    center: Vector2D,
+    from: Vector2D,
+    sweep: f32,
+    radius: f32,
+    detail: EllipseDetail,
+) {
+    let segments = detail.arc_segments(radius, sweep);
+    let start = from.angle();
+
+    let mut previous = center + from * radius;
+    for i in 1..=segments {
+        let angle = start + sweep * i as f32 / segments as f32;
+        let next = center + Vector2D::from_angle(angle) * radius;
+        push_triangle(triangles, center, previous, next);
+        previous = next;
+    }
+}
+
+fn dedup(points: &[Point]) -> Vec<Vector2D> {
+    let mut result: Vec<Vector2D> = Vec::with_capacity(points.len());
+    for &point in points {
+        let point = point.into();
+        match result.last() {
+            Some(&last) if same(last, point) => {}
+            _ => result.push(point),
+        }
+    }
+    result
+}
+
+fn same(a: Vector2D, b: Vector2D) -> bool {
+    (a.x - b.x).abs() < EPSILON && (a.y - b.y).abs() < EPSILON
+}
+
+fn direction(a: Vector2D, b: Vector2D) -> Vector2D {
+    (b - a).normalized()
+}
+
+fn left_normal(d: Vector2D) -> Vector2D {
+    Vector2D::new(-d.y, d.x)
+}
+
+fn push_triangle(triangles: &mut Vec<GlTriangle>, a: Vector2D, b: Vector2D, c: Vector2D) {
+    triangles.push(GlTriangle::new(a.into(), b.into(), c.into()));
+}
+
+fn push_quad(triangles: &mut Vec<GlTriangle>, a: Vector2D, b: Vector2D, c: Vector2D, d: Vector2D) {
+    push_triangle(triangles, a, b, c);
+    push_triangle(triangles, a, c, d);
+}
@@ -1,7 +1,7 @@
 use crate::{
     core::vertex::{vert2d, Vertex},
     primitives::shapes_2d::Point,
-    Color,
+    Color, Matrix2D,
 };
 
 pub struct Tessellate<S, F, P>
@@ -10,6 +10,7 @@ where
 {
     input: S,
     tess_fn: F,
+    matrix: Matrix2D,
 }
 
 impl<S, F, P> Tessellate<S, F, P>
@@ -18,13 +19,25 @@ where
     P: IntoIterator<Item = Point>,
 {
     pub fn new(input: S, tess_fn: F) -> Tessellate<S, F, P> {
-        Tessellate { input, tess_fn }
+        Tessellate {
+            input,
+            tess_fn,
+            matrix: Matrix2D::IDENTITY,
+        }
+    }
+
+    /// Applies `matrix` to the tessellated points, after any previous transform.
+    pub fn transform(mut self, matrix: Matrix2D) -> Tessellate<S, F, P> {
+        self.matrix = matrix * self.matrix;
+        self
     }
 
     pub fn color(self, color: Color) -> impl Iterator<Item = Vertex> {
         let points = (self.tess_fn)(self.input);
-        points
-            .into_iter()
-            .map(move |point| vert2d(point.x, point.y, color))
+        let matrix = self.matrix;
+        points.into_iter().map(move |point| {
+            let (x, y) = matrix.apply_xy(point.x, point.y);
+            vert2d(x, y, color)
+        })
     }
 }
@@ -0,0 +1,165 @@
+//! Golden-image regression testing for sketches.
+//!
+//! A sketch closure is rendered headlessly with the software rasterizer and
+//! compared against a reference PNG. Set `PROCESSING_BLESS=1` to (re)write
+//! the references from the current output.
+
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+
+use crate::{app::Application, GraphicsP2D, Processing};
+
+pub const BLESS_ENV: &str = "PROCESSING_BLESS";
+
+/// Result of comparing two images pixel by pixel.
+#[derive(Debug)]
+pub struct ImageDiff {
+    /// Number of pixels where at least one channel differs by more than the tolerance
+    pub mismatched: usize,
+    /// Largest per-channel difference found
+    pub max_delta: u8,
+    /// Visualisation of the differences: mismatched pixels are red, the rest is a faded copy
+    pub image: RgbaImage,
+}
+
+impl ImageDiff {
+    pub fn is_match(&self) -> bool {
+        self.mismatched == 0
+    }
+}
+
+/// Renders `sketch` as the `setup` of a `width` x `height` sketch and returns the frame.
+pub fn render_sketch(
+    width: u32,
+    height: u32,
+    sketch: impl Fn(&mut Processing<(), GraphicsP2D>) + 'static,
+) -> anyhow::Result<RgbaImage> {
+    crate::new::<GraphicsP2D>()
+        .with_size(width, height)
+        .setup(sketch)
+        .render()
+}
+
+/// Runs `sketch` as the `setup` of a `width` x `height` sketch and tessellates
+/// what it drew without rasterizing it, to time tessellation on its own.
+/// Returns how many vertices and instances were made.
+pub fn tessellate_sketch(
+    width: u32,
+    height: u32,
+    sketch: impl Fn(&mut Processing<(), GraphicsP2D>) + 'static,
+) -> usize {
+    crate::new::<GraphicsP2D>()
+        .with_size(width, height)
+        .setup(sketch)
+        .tessellate()
+}
+
+/// Compares `actual` against `expected`, allowing each channel to be off by `tolerance`.
+pub fn compare(
+    actual: &RgbaImage,
+    expected: &RgbaImage,
+    tolerance: u8,
+) -> anyhow::Result<ImageDiff> {
+    if actual.dimensions() != expected.dimensions() {
+        anyhow::bail!(
+            "image size mismatch: got {:?}, expected {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        );
+    }
+
+    let mut mismatched = 0;
+    let mut max_delta = 0;
+    let mut image = RgbaImage::new(actual.width(), actual.height());
+
+    for (x, y, Rgba(a)) in actual.enumerate_pixels() {
+        let Rgba(e) = expected.get_pixel(x, y);
+        let delta = (0..4).map(|i| a[i].abs_diff(e[i])).max().unwrap_or(0);
+        max_delta = max_delta.max(delta);
+
+        let pixel = if delta > tolerance {
+            mismatched += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            let gray = ((e[0] as u16 + e[1] as u16 + e[2] as u16) / 3 / 4 + 191) as u8;
+            Rgba([gray, gray, gray, 255])
+        };
+        image.put_pixel(x, y, pixel);
+    }
+
+    Ok(ImageDiff {
+        mismatched,
+        max_delta,
+        image,
+    })
+}
+
+/// Renders `sketch` and asserts that it matches the reference PNG at `reference`.
+///
+/// On failure the rendered frame and a diff image are written next to the
+/// reference as `<name>.actual.png` and `<name>.diff.png`.
+///
+/// # Panics
+///
+/// Panics if the sketch fails to render, the reference is missing, or any
+/// pixel differs by more than `tolerance`.
+pub fn assert_golden(
+    reference: impl AsRef<Path>,
+    tolerance: u8,
+    width: u32,
+    height: u32,
+    sketch: impl Fn(&mut Processing<(), GraphicsP2D>) + 'static,
+) {
+    let reference = reference.as_ref();
+    let actual = render_sketch(width, height, sketch)
+        .unwrap_or_else(|err| panic!("failed to render {}: {err}", reference.display()));
+
+    if std::env::var_os(BLESS_ENV).is_some() {
+        if let Some(dir) = reference.parent() {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        actual.save(reference).unwrap();
+        return;
+    }
+
+    let expected = match image::open(reference) {
+        Ok(image) => image.into_rgba8(),
+        Err(err) => panic!(
+            "cannot read reference {}: {err} (run with {BLESS_ENV}=1 to create it)",
+            reference.display()
+        ),
+    };
+
+    let diff = match compare(&actual, &expected, tolerance) {
+        Ok(diff) if diff.is_match() => return,
+        Ok(diff) => diff,
+        Err(err) => {
+            actual.save(sibling(reference, "actual")).unwrap();
+            panic!("{}: {err}", reference.display());
+        }
+    };
+
+    let actual_path = sibling(reference, "actual");
+    let diff_path = sibling(reference, "diff");
+    actual.save(&actual_path).unwrap();
+    diff.image.save(&diff_path).unwrap();
+
+    panic!(
+        "{}: {} pixels differ by more than {} (max delta {}); see {} and {}",
+        reference.display(),
+        diff.mismatched,
+        tolerance,
+        diff.max_delta,
+        actual_path.display(),
+        diff_path.display(),
+    );
+}
+
+fn sibling(reference: &Path, suffix: &str) -> PathBuf {
+    let stem = reference
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    reference.with_file_name(format!("{stem}.{suffix}.png"))
+}
@@ -1,9 +1,30 @@
+use image::RgbaImage;
+use winit::window::Window;
+
 use crate::{
-    geometry::GeometryKind, gl_shape::LazyGlShape, settings::StrokeJoin, Color, StrokeCap,
+    geometry::{CloseMode, GeometryError, GeometryKind},
+    gl_shape::GlFrame,
+    settings::{FillRule, ShapeMode, StrokeJoin},
+    Color, StrokeCap,
 };
 
 pub trait Renderer {
-    fn shapes(&mut self) -> Vec<LazyGlShape>;
+    /// The shapes queued since the last frame was drawn. They are cleared
+    /// once it has been.
+    fn frame(&mut self) -> &mut GlFrame;
+
+    /// Called right before the sketch's `draw` function runs.
+    fn begin_draw(&mut self) {}
+}
+
+/// Turns the tessellated shapes of a frame into pixels.
+pub trait Backend {
+    fn draw(&mut self, frame: &GlFrame, width: u32, height: u32) -> anyhow::Result<()>;
+    fn read_pixels(&self) -> anyhow::Result<RgbaImage>;
+
+    fn window(&self) -> Option<&Window> {
+        None
+    }
 }
 
 pub trait Stroke {
@@ -12,6 +33,12 @@ pub trait Stroke {
     fn stroke_cap(&mut self, cap: StrokeCap);
     fn stroke_join(&mut self, join: StrokeJoin);
     fn fill(&mut self, color: Option<Color>);
+    fn fill_rule(&mut self, rule: FillRule);
+    fn rect_mode(&mut self, mode: ShapeMode);
+    fn ellipse_mode(&mut self, mode: ShapeMode);
+
+    fn push_style(&mut self);
+    fn pop_style(&mut self);
 }
 
 pub trait BeginShape {
@@ -19,5 +46,7 @@ pub trait BeginShape {
 
     fn begin_shape(&mut self, kind: GeometryKind);
     fn vertex(&mut self, vertex: Self::Item);
-    fn end_shape(&mut self);
+    fn begin_contour(&mut self);
+    fn end_contour(&mut self);
+    fn end_shape(&mut self, close: CloseMode) -> Result<(), GeometryError>;
 }
@@ -7,3 +7,87 @@ where
 {
     from + (to - from) * t
 }
+
+/// Point at `t` (0 to 1) on the cubic Bezier curve from `a` to `d` with
+/// control points `b` and `c`. Works on single coordinates as well as vectors.
+///
+/// # Examples
+///
+/// ```
+/// use processing::{bezier_point, Vector2D};
+///
+/// assert_eq!(bezier_point(0.0, 0.0, 1.0, 1.0, 0.5), 0.5);
+///
+/// let (a, d) = (Vector2D::new(0.0, 0.0), Vector2D::new(4.0, 0.0));
+/// let top = bezier_point(a, Vector2D::new(0.0, 4.0), Vector2D::new(4.0, 4.0), d, 0.5);
+/// assert_eq!(top, Vector2D::new(2.0, 3.0));
+/// ```
+pub fn bezier_point<T>(a: T, b: T, c: T, d: T, t: f32) -> T
+where
+    T: std::ops::Add<Output = T> + std::ops::Mul<f32, Output = T> + Copy,
+{
+    let u = 1.0 - t;
+    a * (u * u * u) + b * (3.0 * u * u * t) + c * (3.0 * u * t * t) + d * (t * t * t)
+}
+
+/// Derivative of `bezier_point` with respect to `t`.
+pub fn bezier_tangent<T>(a: T, b: T, c: T, d: T, t: f32) -> T
+where
+    T: std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<f32, Output = T>
+        + Copy,
+{
+    let u = 1.0 - t;
+    (b - a) * (3.0 * u * u) + (c - b) * (6.0 * u * t) + (d - c) * (3.0 * t * t)
+}
+
+/// Control points of the cubic Bezier curve matching the Catmull-Rom segment
+/// from `b` to `c`, with `a` and `d` as the neighbouring points.
+///
+/// A `tightness` of 0 gives a Catmull-Rom spline, 1 gives straight lines.
+pub fn curve_to_bezier<T>(a: T, b: T, c: T, d: T, tightness: f32) -> [T; 4]
+where
+    T: std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<f32, Output = T>
+        + Copy,
+{
+    let s = (1.0 - tightness) / 6.0;
+    [b, b + (c - a) * s, c - (d - b) * s, c]
+}
+
+/// Point at `t` (0 to 1) on the curve segment from `b` to `c`, see `curve_to_bezier`.
+///
+/// # Examples
+///
+/// ```
+/// use processing::curve_point;
+///
+/// // evenly spaced points on a line give the line back
+/// assert_eq!(curve_point(0.0, 1.0, 2.0, 3.0, 0.25, 0.0), 1.25);
+/// // fully tight curves go straight from `b` to `c`
+/// assert_eq!(curve_point(0.0, 1.0, 5.0, 0.0, 0.5, 1.0), 3.0);
+/// ```
+pub fn curve_point<T>(a: T, b: T, c: T, d: T, t: f32, tightness: f32) -> T
+where
+    T: std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<f32, Output = T>
+        + Copy,
+{
+    let [a, b, c, d] = curve_to_bezier(a, b, c, d, tightness);
+    bezier_point(a, b, c, d, t)
+}
+
+/// Derivative of `curve_point` with respect to `t`.
+pub fn curve_tangent<T>(a: T, b: T, c: T, d: T, t: f32, tightness: f32) -> T
+where
+    T: std::ops::Add<Output = T>
+        + std::ops::Sub<Output = T>
+        + std::ops::Mul<f32, Output = T>
+        + Copy,
+{
+    let [a, b, c, d] = curve_to_bezier(a, b, c, d, tightness);
+    bezier_tangent(a, b, c, d, t)
+}
@@ -18,18 +18,18 @@ impl Vector2D {
     /// # Examples
     ///
     /// ```
-    /// use math::Vector2D;
+    /// use processing::Vector2D;
     ///
     /// let v = Vector2D::from_angle(std::f32::consts::PI);
     /// assert_eq!(v.x, -1.0);
-    /// assert_eq!(v.y, 0.0);
+    /// assert!(v.y.abs() < 1e-6);
     /// assert_eq!(v.magnitude(), 1.0);
     /// ```
     /// ```
-    /// use math::Vector2D;
+    /// use processing::Vector2D;
     ///
     /// let v = Vector2D::from_angle(std::f32::consts::PI / 2.0) * 3.0;
-    /// assert_eq!(v.x, 0.0);
+    /// assert!(v.x.abs() < 1e-6);
     /// assert_eq!(v.y, 3.0);
     /// assert_eq!(v.magnitude(), 3.0);
     /// ```
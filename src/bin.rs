@@ -8,8 +8,27 @@ mod examples;
 //     frame: i64,
 // }
 
+/// Usage: `main [noise|trees|l-systems] [output.png]`
+///
+/// When an output path is given the sketch is rendered with the software
+/// rasterizer and saved instead of opening a window.
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // examples::trees::app().run()?;
-    examples::noise::app().run()?;
+    let mut args = std::env::args().skip(1);
+    let example = args.next();
+    let output = args.next();
+
+    match example.as_deref() {
+        Some("trees") => launch(examples::trees::app(), output)?,
+        Some("l-systems") => launch(examples::l_systems::main::app(), output)?,
+        _ => launch(examples::noise::app(), output)?,
+    }
+    Ok(())
+}
+
+fn launch(app: impl Application, output: Option<String>) -> anyhow::Result<()> {
+    match output {
+        Some(path) => app.render()?.save(path)?,
+        None => app.run()?,
+    }
     Ok(())
 }
@@ -15,9 +15,6 @@ pub fn draw(config: &LSystemConfig, start_pos: Vector2D, iterations: usize, p: &
 
     for iteration in 1..=iterations {
         let generation = config.generate(iteration);
-        let mut current_pos = start_pos;
-        let mut current_angle = HALF_PI;
-        let mut stack = vec![];
 
         let branch_length = 80.0 / iteration as f32;
 
@@ -31,26 +28,25 @@ pub fn draw(config: &LSystemConfig, start_pos: Vector2D, iterations: usize, p: &
         );
         p.stroke(stroke);
 
+        p.push_matrix();
+        p.translate(start_pos.x, start_pos.y);
+        p.rotate(HALF_PI);
+
         for c in generation.chars() {
             match c {
                 'F' => {
-                    let new_pos = current_pos + Vector2D::from_angle(current_angle) * branch_length;
-                    p.line(current_pos.x, current_pos.y, new_pos.x, new_pos.y);
-                    current_pos = new_pos;
-                }
-                '+' => current_angle += EIGHTH_PI,
-                '-' => current_angle -= EIGHTH_PI,
-                '[' => {
-                    stack.push((current_pos, current_angle));
-                }
-                ']' => {
-                    let (pos, angle) = stack.pop().unwrap();
-                    current_pos = pos;
-                    current_angle = angle;
+                    p.line(0.0, 0.0, branch_length, 0.0);
+                    p.translate(branch_length, 0.0);
                 }
+                '+' => p.rotate(EIGHTH_PI),
+                '-' => p.rotate(-EIGHTH_PI),
+                '[' => p.push_matrix(),
+                ']' => p.pop_matrix(),
                 _ => {}
             }
         }
+
+        p.pop_matrix();
     }
 }
 
@@ -13,6 +13,8 @@ pub fn app() -> impl Application {
             p.stroke_cap(p5::StrokeCap::Round);
             let mut max = 0.0;
             let mut min = 0.0;
+            let mut points = Vec::new();
+            let mut colors = Vec::new();
             for x in 0..p.width() {
                 for y in 0..p.height() {
                     let n = noise.get([x as f64 / 20.0, y as f64 / 20.0]) * 122.0 + 122.0;
@@ -24,10 +26,11 @@ pub fn app() -> impl Application {
                         min = n;
                     }
 
-                    p.stroke(Color::red(n as u8));
-                    p.point(x as f32, y as f32);
+                    points.push((x as f32, y as f32));
+                    colors.push(Color::red(n as u8));
                 }
             }
+            p.colored_points(&points, &colors);
 
             println!("max: {}, min: {}", max, min);
         })
@@ -1,4 +1,4 @@
-
+pub use main::app;
 
 mod main;
 mod tree;
@@ -20,6 +20,7 @@ impl MaxToMinFn {
     }
 }
 
+#[allow(dead_code)]
 pub struct BranchOptions {}
 
 #[derive(Debug, Clone, Copy)]
@@ -45,6 +46,7 @@ impl Branch {
         Branch::new(start, end, width, color)
     }
 
+    #[allow(dead_code)]
     fn length(&self) -> f32 {
         self.start.distance(self.end)
     }
@@ -54,9 +56,11 @@ impl Branch {
     }
 
     fn draw(&self, p: &mut Processing<(), GraphicsP2D>) {
+        p.push_style();
         p.stroke(self.color);
         p.stroke_weight(self.width);
         p.line(self.start.x, self.start.y, self.end.x, self.end.y);
+        p.pop_style();
     }
 }
 
@@ -0,0 +1,643 @@
+use processing::{
+    testing::{assert_golden, compare, render_sketch},
+    ArcMode, CloseMode, Color, FillRule, GeometryKind, GraphicsP2D, Path, Processing, ShapeMode,
+    StrokeCap, StrokeJoin, HALF_PI, QUARTER_PI, TAU,
+};
+
+const TOLERANCE: u8 = 2;
+
+fn golden(name: &str) -> String {
+    format!("{}/tests/golden/{name}.png", env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Checks that two `width` x `height` sketches draw exactly the same pixels,
+/// not just close ones.
+fn assert_same_pixels(
+    width: u32,
+    height: u32,
+    expected: impl Fn(&mut Processing<(), GraphicsP2D>) + 'static,
+    actual: impl Fn(&mut Processing<(), GraphicsP2D>) + 'static,
+) {
+    let expected = render_sketch(width, height, expected).unwrap();
+    let actual = render_sketch(width, height, actual).unwrap();
+    let diff = compare(&actual, &expected, 0).unwrap();
+    assert!(
+        diff.is_match(),
+        "{} pixels differ (max delta {})",
+        diff.mismatched,
+        diff.max_delta
+    );
+}
+
+#[test]
+fn background() {
+    assert_golden(golden("background"), TOLERANCE, 64, 48, |p| {
+        p.background(Color::hex(0x84c3e3));
+    });
+}
+
+#[test]
+fn translucent_background() {
+    assert_golden(golden("translucent_background"), TOLERANCE, 64, 48, |p| {
+        p.background(Color::WHITE);
+        p.background(Color::BLUE.with_alpha(128));
+    });
+}
+
+#[test]
+fn triangle() {
+    assert_golden(golden("triangle"), TOLERANCE, 64, 64, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::RED);
+        p.stroke_weight(3.0);
+        p.triangle(8.0, 8.0, 56.0, 16.0, 24.0, 56.0);
+    });
+}
+
+#[test]
+fn rect_and_square() {
+    assert_golden(golden("rect_and_square"), TOLERANCE, 64, 64, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::GREEN);
+        p.rect(4.0, 4.0, 32.0, 20.0);
+        p.no_stroke();
+        p.fill(Color::BLUE.with_alpha(128));
+        p.square(20.0, 16.0, 36.0);
+    });
+}
+
+#[test]
+fn ellipse() {
+    assert_golden(golden("ellipse"), TOLERANCE, 64, 64, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::YELLOW);
+        p.stroke_weight(2.0);
+        p.ellipse(32.0, 32.0, 24.0, 16.0);
+    });
+}
+
+#[test]
+fn line_caps() {
+    assert_golden(golden("line_caps"), TOLERANCE, 64, 64, |p| {
+        p.background(Color::WHITE);
+        p.stroke_weight(8.0);
+        for (i, cap) in [StrokeCap::Butt, StrokeCap::Round, StrokeCap::Square]
+            .into_iter()
+            .enumerate()
+        {
+            let y = 16.0 + i as f32 * 16.0;
+            p.stroke_cap(cap);
+            p.line(16.0, y, 48.0, y);
+        }
+    });
+}
+
+#[test]
+fn transforms() {
+    assert_golden(golden("transforms"), TOLERANCE, 64, 64, |p| {
+        p.background(Color::WHITE);
+        p.no_stroke();
+        p.translate(32.0, 32.0);
+        for i in 0..4 {
+            p.push_matrix();
+            p.rotate(i as f32 * HALF_PI);
+            p.translate(8.0, 0.0);
+            p.scale_xy(2.0, 1.0);
+            p.fill(Color::rgb(64 * i, 0, 255 - 64 * i));
+            p.rect(0.0, -4.0, 8.0, 8.0);
+            p.pop_matrix();
+        }
+        p.fill(Color::BLACK);
+        p.shear_x(QUARTER_PI);
+        p.square(-4.0, -4.0, 8.0);
+    });
+}
+
+#[test]
+fn push_pop() {
+    assert_golden(golden("push_pop"), TOLERANCE, 64, 64, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::RED);
+        p.stroke_weight(4.0);
+        p.push();
+        p.translate(32.0, 32.0);
+        p.fill(Color::BLUE);
+        p.no_stroke();
+        p.square(0.0, 0.0, 24.0);
+        p.pop();
+        p.square(8.0, 8.0, 16.0);
+    });
+}
+
+#[test]
+#[should_panic(expected = "pop_style() has been called more times than push_style()")]
+fn unbalanced_pop_style() {
+    processing::testing::render_sketch(8, 8, |p| {
+        p.push_style();
+        p.pop_style();
+        p.pop_style();
+    })
+    .unwrap();
+}
+
+#[test]
+fn stroke_joins() {
+    assert_golden(golden("stroke_joins"), TOLERANCE, 96, 48, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::LIGHT_GRAY);
+        p.stroke(Color::BLACK.with_alpha(160));
+        p.stroke_weight(6.0);
+        for (i, join) in [StrokeJoin::Miter, StrokeJoin::Bevel, StrokeJoin::Round]
+            .into_iter()
+            .enumerate()
+        {
+            let x = 8.0 + i as f32 * 30.0;
+            p.stroke_join(join);
+            p.triangle(x, 8.0, x + 20.0, 12.0, x + 4.0, 40.0);
+        }
+    });
+}
+
+#[test]
+fn translucent_strokes() {
+    assert_golden(golden("translucent_strokes"), TOLERANCE, 96, 48, |p| {
+        p.background(Color::WHITE);
+        p.no_fill();
+        p.stroke(Color::BLACK.with_alpha(100));
+        p.stroke_weight(8.0);
+        p.stroke_cap(StrokeCap::Round);
+        p.stroke_join(StrokeJoin::Round);
+        p.line(8.0, 8.0, 88.0, 8.0);
+        p.triangle(8.0, 20.0, 40.0, 24.0, 16.0, 40.0);
+        p.ellipse(68.0, 30.0, 12.0, 12.0);
+    });
+}
+
+#[test]
+fn polygon_fill_rules() {
+    assert_golden(golden("polygon_fill_rules"), TOLERANCE, 96, 48, |p| {
+        p.background(Color::WHITE);
+        p.no_stroke();
+        p.fill(Color::BLUE.with_alpha(128));
+        for (i, rule) in [FillRule::NonZero, FillRule::EvenOdd]
+            .into_iter()
+            .enumerate()
+        {
+            let x = 16.0 + i as f32 * 32.0;
+            p.fill_rule(rule);
+            p.begin_shape(GeometryKind::Polygon);
+            for k in 0..5 {
+                let angle = HALF_PI + k as f32 * 2.0 * TAU / 5.0;
+                p.vertex((x + 14.0 * angle.cos(), 24.0 + 14.0 * angle.sin()));
+            }
+            p.end_shape(CloseMode::Open).unwrap();
+        }
+
+        // concave outline with a hole
+        p.fill(Color::RED);
+        p.begin_shape(GeometryKind::Polygon);
+        for vertex in [
+            (68.0, 8.0),
+            (92.0, 8.0),
+            (92.0, 40.0),
+            (80.0, 24.0),
+            (68.0, 40.0),
+        ] {
+            p.vertex(vertex);
+        }
+        p.begin_contour();
+        for vertex in [(74.0, 12.0), (74.0, 18.0), (86.0, 18.0), (86.0, 12.0)] {
+            p.vertex(vertex);
+        }
+        p.end_contour();
+        p.end_shape(CloseMode::Open).unwrap();
+    });
+}
+
+#[test]
+fn begin_shape_strokes() {
+    assert_golden(golden("begin_shape_strokes"), TOLERANCE, 96, 48, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::LIGHT_GRAY);
+        p.stroke_weight(4.0);
+        p.stroke_join(StrokeJoin::Round);
+
+        // the stroke of each segment comes from the vertex it starts at
+        p.begin_shape(GeometryKind::Polygon);
+        p.vertex((6.0, 6.0));
+        p.vertex((38.0, 6.0));
+        p.stroke(Color::RED);
+        p.stroke_weight(2.0);
+        p.vertex((38.0, 38.0));
+        p.vertex((6.0, 38.0));
+        p.end_shape(CloseMode::Close).unwrap();
+
+        p.stroke(Color::BLUE);
+        p.stroke_weight(6.0);
+        p.stroke_cap(StrokeCap::Round);
+        p.begin_shape(GeometryKind::Points);
+        p.vertex((52.0, 40.0));
+        p.vertex((64.0, 40.0));
+        p.end_shape(CloseMode::Open).unwrap();
+
+        p.stroke_cap(StrokeCap::Square);
+        p.begin_shape(GeometryKind::Lines);
+        p.vertex((52.0, 26.0));
+        p.vertex((88.0, 30.0));
+        p.vertex((52.0, 8.0));
+        p.vertex((88.0, 16.0));
+        p.end_shape(CloseMode::Open).unwrap();
+    });
+}
+
+#[test]
+fn quads() {
+    assert_golden(golden("quads"), TOLERANCE, 96, 48, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::LIGHT_GRAY);
+        p.stroke_weight(2.0);
+
+        p.begin_shape(GeometryKind::Quads);
+        for vertex in [(6.0, 6.0), (40.0, 10.0), (36.0, 40.0), (10.0, 34.0)] {
+            p.vertex(vertex);
+        }
+        p.end_shape(CloseMode::Open).unwrap();
+
+        p.begin_shape(GeometryKind::QuadStrip);
+        for i in 0..4 {
+            let x = 52.0 + i as f32 * 12.0;
+            p.vertex((x, 8.0));
+            p.vertex((x, 40.0 - i as f32 * 4.0));
+        }
+        p.end_shape(CloseMode::Open).unwrap();
+    });
+}
+
+#[test]
+fn translucent_strip_strokes() {
+    let image = render_sketch(96, 48, |p| {
+        p.background(Color::WHITE);
+        p.no_fill();
+        p.stroke(Color::rgba(0, 0, 255, 128));
+        p.stroke_weight(4.0);
+
+        p.begin_shape(GeometryKind::TriangleStrip);
+        for vertex in [(8.0, 8.0), (8.0, 40.0), (40.0, 8.0), (40.0, 40.0)] {
+            p.vertex(vertex);
+        }
+        p.end_shape(CloseMode::Open).unwrap();
+
+        p.begin_shape(GeometryKind::TriangleFan);
+        for vertex in [(56.0, 8.0), (88.0, 8.0), (88.0, 40.0), (56.0, 40.0)] {
+            p.vertex(vertex);
+        }
+        p.end_shape(CloseMode::Open).unwrap();
+    })
+    .unwrap();
+
+    // the diagonals are shared by both triangles, but blended only once
+    assert_eq!(image.get_pixel(24, 24), image.get_pixel(8, 24));
+    assert_eq!(image.get_pixel(72, 24), image.get_pixel(56, 24));
+}
+
+#[test]
+fn invalid_vertex_count() {
+    processing::testing::render_sketch(8, 8, |p| {
+        p.begin_shape(GeometryKind::Quads);
+        for i in 0..6 {
+            p.vertex((i as f32, 0.0));
+        }
+        let err = p.end_shape(CloseMode::Open).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "a Quads shape cannot have 6 vertices, it needs a multiple of 4"
+        );
+    })
+    .unwrap();
+}
+
+#[test]
+fn curves() {
+    assert_golden(golden("curves"), TOLERANCE, 96, 48, |p| {
+        p.background(Color::WHITE);
+        p.stroke_weight(2.0);
+        p.fill(Color::LIGHT_GRAY);
+        p.bezier(4.0, 4.0, 4.0, 44.0, 36.0, 44.0, 36.0, 4.0);
+
+        p.no_fill();
+        p.stroke(Color::RED);
+        p.begin_shape(GeometryKind::Polygon);
+        for (x, y) in [
+            (40.0, 8.0),
+            (44.0, 8.0),
+            (56.0, 40.0),
+            (64.0, 8.0),
+            (76.0, 40.0),
+            (80.0, 40.0),
+        ] {
+            p.curve_vertex(x, y);
+        }
+        p.end_shape(CloseMode::Open).unwrap();
+
+        p.fill(Color::BLUE);
+        p.no_stroke();
+        p.begin_shape(GeometryKind::Polygon);
+        p.vertex((80.0, 4.0));
+        p.quadratic_vertex(92.0, 4.0, 92.0, 16.0);
+        p.bezier_vertex(92.0, 24.0, 80.0, 24.0, 80.0, 16.0);
+        p.end_shape(CloseMode::Close).unwrap();
+    });
+}
+
+#[test]
+fn paths() {
+    assert_golden(golden("paths"), TOLERANCE, 96, 48, |p| {
+        p.background(Color::WHITE);
+        p.stroke_weight(2.0);
+        p.fill(Color::LIGHT_GRAY);
+
+        // rounded frame around a square hole
+        let frame = Path::new()
+            .move_to(24.0, 4.0)
+            .arc_to(44.0, 4.0, 44.0, 44.0, 8.0)
+            .arc_to(44.0, 44.0, 4.0, 44.0, 8.0)
+            .arc_to(4.0, 44.0, 4.0, 4.0, 8.0)
+            .arc_to(4.0, 4.0, 44.0, 4.0, 8.0)
+            .close()
+            .move_to(16.0, 16.0)
+            .line_to(32.0, 16.0)
+            .line_to(32.0, 32.0)
+            .line_to(16.0, 32.0)
+            .close();
+        p.fill_rule(FillRule::EvenOdd);
+        p.path(&frame);
+
+        p.fill(Color::BLUE.with_alpha(128));
+        p.stroke(Color::RED.with_alpha(128));
+        let lens = Path::new()
+            .move_to(52.0, 8.0)
+            .cubic_to(52.0, 44.0, 92.0, 44.0, 92.0, 8.0)
+            .quad_to(72.0, 28.0, 52.0, 8.0)
+            .close();
+        p.path(&lens);
+    });
+}
+
+#[test]
+fn shape_modes() {
+    assert_golden(golden("shape_modes"), TOLERANCE, 96, 48, |p| {
+        p.background(Color::WHITE);
+        p.no_fill();
+        p.stroke_weight(2.0);
+
+        // the same 24px wide shapes placed with each mode
+        p.stroke(Color::RED);
+        p.rect_mode(ShapeMode::Corner);
+        p.square(12.0, 12.0, 24.0);
+        p.ellipse_mode(ShapeMode::Center);
+        p.circle(24.0, 24.0, 24.0);
+
+        p.stroke(Color::BLUE);
+        p.rect_mode(ShapeMode::Center);
+        p.rect(72.0, 24.0, 24.0, 24.0);
+        p.ellipse_mode(ShapeMode::Radius);
+        p.ellipse(72.0, 24.0, 12.0, 12.0);
+        p.rect_mode(ShapeMode::Corners);
+        p.rect(64.0, 16.0, 80.0, 32.0);
+        p.ellipse_mode(ShapeMode::Corners);
+        p.ellipse(80.0, 32.0, 64.0, 16.0);
+    });
+}
+
+#[test]
+fn arc_modes() {
+    assert_golden(golden("arc_modes"), TOLERANCE, 144, 48, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::YELLOW);
+        p.stroke(Color::BLACK);
+        p.stroke_weight(2.0);
+
+        p.arc(24.0, 24.0, 36.0, 36.0, 0.0, TAU - HALF_PI, ArcMode::Open);
+        p.arc(72.0, 24.0, 36.0, 36.0, 0.0, TAU - HALF_PI, ArcMode::Chord);
+        // reversed and a turn further: the same arc as the others
+        let stop = TAU - HALF_PI + TAU;
+        p.arc(120.0, 24.0, 36.0, 36.0, stop, TAU, ArcMode::Pie);
+    });
+}
+
+#[test]
+fn ellipse_detail() {
+    assert_golden(golden("ellipse_detail"), TOLERANCE, 144, 48, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::YELLOW);
+        p.stroke(Color::BLACK);
+
+        // a small circle scaled up stays as smooth as a large one
+        p.push_matrix();
+        p.translate(24.0, 24.0);
+        p.scale(10.0);
+        p.stroke_weight(0.2);
+        p.circle(0.0, 0.0, 4.0);
+        p.pop_matrix();
+        p.stroke_weight(2.0);
+        p.circle(72.0, 24.0, 40.0);
+
+        p.ellipse_detail(Some(6));
+        p.circle(120.0, 24.0, 40.0);
+    });
+}
+
+#[test]
+fn rounded_rects() {
+    assert_golden(golden("rounded_rects"), TOLERANCE, 144, 48, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::YELLOW);
+        p.stroke(Color::BLACK);
+        p.stroke_weight(2.0);
+
+        p.rounded_rect(6.0, 6.0, 36.0, 36.0, 8.0);
+        // radii larger than half the side make a circle
+        p.rounded_rect(54.0, 6.0, 36.0, 36.0, 100.0);
+        // only the top left and bottom right corners rounded
+        p.rounded_rect(102.0, 6.0, 36.0, 36.0, [12.0, 0.0, 12.0, 0.0]);
+    });
+}
+
+#[test]
+fn polygons_and_stars() {
+    assert_golden(golden("polygons_and_stars"), TOLERANCE, 192, 48, |p| {
+        p.background(Color::WHITE);
+        p.fill(Color::YELLOW);
+        p.stroke(Color::BLACK);
+        p.stroke_weight(2.0);
+
+        // concave, with the reflex corner on the second point
+        p.quad(6.0, 6.0, 24.0, 20.0, 42.0, 6.0, 24.0, 42.0);
+        p.polygon(72.0, 24.0, 18.0, 6, 0.0);
+        p.polygon(120.0, 24.0, 18.0, 4, QUARTER_PI);
+        p.star(168.0, 24.0, 8.0, 20.0, 5);
+    });
+}
+
+/// Hundreds of translucent shapes of every kind, enough to be tessellated on
+/// several threads.
+fn crowd(p: &mut Processing<(), GraphicsP2D>, threads: usize) {
+    p.tessellation_threads(Some(threads));
+    p.background(Color::WHITE);
+    p.stroke_weight(1.5);
+    for i in 0..400 {
+        let (x, y) = ((i % 20) as f32 * 6.0 + 3.0, (i / 20) as f32 * 6.0 + 3.0);
+        p.fill(Color::rgba((i * 7 % 256) as u8, 80, 160, 160));
+        p.stroke(Color::rgba(0, 0, 0, 120));
+        match i % 4 {
+            0 => p.circle(x, y, 7.0),
+            1 => p.rect(x - 2.5, y - 2.5, 5.0, 5.0),
+            2 => p.line(x - 3.0, y - 3.0, x + 3.0, y + 3.0),
+            _ => {
+                p.begin_shape(GeometryKind::Polygon);
+                p.vertex((x - 3.0, y - 3.0));
+                p.vertex((x + 3.0, y - 3.0));
+                p.vertex((x, y + 3.0));
+                p.end_shape(CloseMode::Close).unwrap();
+            }
+        }
+    }
+}
+
+#[test]
+fn parallel_tessellation() {
+    assert_golden(golden("parallel_tessellation"), TOLERANCE, 120, 120, |p| {
+        crowd(p, 4)
+    });
+
+    assert_same_pixels(120, 120, |p| crowd(p, 1), |p| crowd(p, 4));
+}
+
+/// A grid of dots and a fan of translucent lines with `cap` ends, each in its
+/// own color, under a polyline.
+fn bulk(p: &mut Processing<(), GraphicsP2D>, cap: StrokeCap, one_by_one: bool) {
+    p.background(Color::WHITE);
+    p.stroke_weight(3.0);
+    p.stroke_cap(cap);
+
+    let points: Vec<_> = (0..64)
+        .map(|i| ((i % 8) as f32 * 8.0 + 4.0, (i / 8) as f32 * 8.0 + 4.0))
+        .collect();
+    let colors: Vec<_> = (0..64).map(|i| Color::rgb(i * 4, 0, 255 - i * 4)).collect();
+    let lines: Vec<_> = (0..8)
+        .map(|i| [(32.0, 4.0), (i as f32 * 8.0 + 4.0, 60.0)])
+        .collect();
+    let line_colors: Vec<_> = (0..8).map(|i| Color::rgba(0, i * 32, 0, 140)).collect();
+
+    if one_by_one {
+        for (&(x, y), &color) in points.iter().zip(&colors) {
+            p.stroke(color);
+            p.point(x, y);
+        }
+        for (&[(x1, y1), (x2, y2)], &color) in lines.iter().zip(&line_colors) {
+            p.stroke(color);
+            p.line(x1, y1, x2, y2);
+        }
+    } else {
+        p.colored_points(&points, &colors);
+        p.colored_lines(&lines, &line_colors);
+    }
+
+    p.stroke(Color::BLACK);
+    p.stroke_join(StrokeJoin::Round);
+    p.polyline(&[(4.0, 32.0), (20.0, 48.0), (44.0, 16.0), (60.0, 32.0)]);
+}
+
+#[test]
+fn bulk_points_and_lines() {
+    assert_golden(golden("bulk_points_and_lines"), TOLERANCE, 64, 64, |p| {
+        bulk(p, StrokeCap::Round, false)
+    });
+
+    // drawing them in bulk changes nothing but the speed
+    assert_same_pixels(
+        64,
+        64,
+        |p| bulk(p, StrokeCap::Round, true),
+        |p| bulk(p, StrokeCap::Round, false),
+    );
+}
+
+#[test]
+fn bulk_square_caps() {
+    // instanced squares and rects, which only differ from the strokes by
+    // rounding along their edges
+    for one_by_one in [false, true] {
+        assert_golden(golden("bulk_square_caps"), TOLERANCE, 64, 64, move |p| {
+            bulk(p, StrokeCap::Square, one_by_one)
+        });
+    }
+}
+
+/// Hundreds of translucent circles, rects and rounded rects, in runs long
+/// enough to be instanced unless each shape also has a stroke.
+fn particles(p: &mut Processing<(), GraphicsP2D>, stroke: Option<Color>) {
+    p.background(Color::WHITE);
+    match stroke {
+        Some(color) => p.stroke(color),
+        None => p.no_stroke(),
+    }
+    p.translate(48.0, 48.0);
+    p.rotate(0.3);
+    for i in 0..300 {
+        let angle = i as f32 * 0.37;
+        let distance = (i % 100) as f32 * 0.4;
+        let (x, y) = (angle.cos() * distance, angle.sin() * distance);
+        p.fill(Color::rgba(
+            (i * 5 % 256) as u8,
+            120,
+            (255 - i % 256) as u8,
+            140,
+        ));
+        match i / 100 {
+            0 => p.circle(x, y, 6.0),
+            1 => p.rect(x, y, 5.0, 3.0),
+            _ => p.rounded_rect(x, y, 6.0, 6.0, 2.0),
+        }
+    }
+}
+
+#[test]
+fn instanced_particles() {
+    assert_golden(golden("instanced_particles"), TOLERANCE, 96, 96, |p| {
+        particles(p, None)
+    });
+
+    // a stroke that draws nothing keeps the fills from being instanced
+    assert_same_pixels(
+        96,
+        96,
+        |p| particles(p, Some(Color::rgba(0, 0, 0, 0))),
+        |p| particles(p, None),
+    );
+}
+
+#[test]
+fn sdf_shapes() {
+    assert_golden(golden("sdf_shapes"), TOLERANCE, 128, 64, |p| {
+        p.sdf_shapes(true);
+        p.background(Color::WHITE);
+        p.fill(Color::YELLOW);
+        p.stroke(Color::BLACK);
+        p.stroke_weight(3.0);
+        p.ellipse(20.0, 44.0, 32.0, 20.0);
+        p.circle(56.0, 44.0, 24.0);
+        p.rounded_rect(76.0, 32.0, 44.0, 24.0, [10.0, 2.0, 10.0, 2.0]);
+
+        p.translate(64.0, 16.0);
+        p.rotate(0.2);
+        p.stroke_weight(6.0);
+        p.stroke_cap(StrokeCap::Round);
+        p.stroke(Color::BLUE.with_alpha(160));
+        p.line(-48.0, 0.0, -8.0, 0.0);
+        p.point(4.0, 0.0);
+        p.no_stroke();
+        p.fill(Color::RED.with_alpha(160));
+        p.ellipse(32.0, 0.0, 36.0, 14.0);
+    });
+}